@@ -0,0 +1,58 @@
+//! 大日程上反复查询空闲时间段的性能
+//!
+//! 运行：`cargo bench --bench free_gaps`
+
+use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
+
+use timeblock::data::block::Block;
+use timeblock::data::day::Schedule;
+use timeblock::data::timeofday::TimeOfDay;
+
+/// 每分钟一个时间段、中间留一分钟空闲，铺满整天
+fn large_schedule() -> Schedule {
+    let mut schedule = Schedule::new();
+    for minute in (0..24 * 60 - 1).step_by(2) {
+        let start = TimeOfDay::new(minute / 60, minute % 60).unwrap();
+        let end = TimeOfDay::new((minute + 1) / 60, (minute + 1) % 60).unwrap();
+        let block = Block::builder()
+            .start_time(start)
+            .end_time(end)
+            .name(format!("任务{}", minute))
+            .build()
+            .unwrap();
+        schedule.add_block(block).unwrap();
+    }
+    schedule
+}
+
+fn bench_free_gaps(c: &mut Criterion) {
+    let schedule = large_schedule();
+    let day_start = TimeOfDay::MIDNIGHT;
+    let day_end = TimeOfDay::END_OF_DAY;
+
+    // 两组都在克隆出的日程上计时同一次 free_gaps 调用，区别只在于缓存是否已预热
+    let mut group = c.benchmark_group("free_gaps");
+    group.bench_function("命中缓存", |b| {
+        b.iter_batched_ref(
+            || {
+                let warmed = schedule.clone();
+                warmed.free_gaps(day_start, day_end);
+                warmed
+            },
+            |warmed| warmed.free_gaps(black_box(day_start), black_box(day_end)),
+            BatchSize::SmallInput,
+        )
+    });
+    // 克隆出的日程不带缓存，每次调用都遍历全部时间段
+    group.bench_function("无缓存", |b| {
+        b.iter_batched_ref(
+            || schedule.clone(),
+            |cold| cold.free_gaps(black_box(day_start), black_box(day_end)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_free_gaps);
+criterion_main!(benches);
@@ -0,0 +1,94 @@
+//! 时间轴组件示例：显示一份示例日程，点击时间段查看详情
+//!
+//! 运行：`cargo run --example timeline --features gui`
+
+use eframe::egui;
+use timeblock::data::block::Block;
+use timeblock::data::day::Schedule;
+use timeblock::gui::{TimelineEvent, TimelineWidget};
+
+/// 常见系统中的中文字体路径（egui 默认字体不含中文字形）
+const CJK_FONT_PATHS: &[&str] = &[
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/System/Library/Fonts/PingFang.ttc",
+    "C:\\Windows\\Fonts\\msyh.ttc",
+];
+
+struct TimelineApp {
+    schedule: Schedule,
+    selected: Option<usize>,
+}
+
+impl TimelineApp {
+    fn new(cc: &eframe::CreationContext<'_>) -> TimelineApp {
+        install_cjk_font(&cc.egui_ctx);
+        TimelineApp {
+            schedule: Schedule::from_text(
+                "08:00-08:30 早餐\n\
+                 09:00-10:30 会议 | 项目讨论\n\
+                 10:30-12:00 写代码\n\
+                 12:00-13:00 午饭\n\
+                 14:00-15:00 代码评审 | 审阅本周的合并请求\n",
+            )
+            .expect("示例日程合法"),
+            selected: None,
+        }
+    }
+
+    fn selected_block(&self) -> Option<&Block> {
+        self.selected.and_then(|index| self.schedule.blocks().get(index))
+    }
+}
+
+impl eframe::App for TimelineApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("details").show(ctx, |ui| match self.selected_block() {
+            Some(block) => {
+                ui.heading(block.name());
+                ui.label(format!("{}-{}", block.start_time(), block.end_time()));
+                ui.label(block.description().unwrap_or("（无描述）"));
+            }
+            None => {
+                ui.label("点击时间段查看详情，拖拽非固定时间段可调整时间");
+            }
+        });
+        egui::CentralPanel::default().show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let event = TimelineWidget::editable(&mut self.schedule)
+                    .selected(self.selected)
+                    .show(ui);
+                match event {
+                    Some(TimelineEvent::BlockClicked(index)) | Some(TimelineEvent::BlockMoved(index)) => {
+                        self.selected = Some(index);
+                    }
+                    Some(TimelineEvent::MoveRejected(_)) | None => {}
+                }
+            });
+        });
+    }
+}
+
+/// 加载第一个可用的系统中文字体
+fn install_cjk_font(ctx: &egui::Context) {
+    let Some(bytes) = CJK_FONT_PATHS.iter().find_map(|path| std::fs::read(path).ok()) else {
+        return;
+    };
+    let mut fonts = egui::FontDefinitions::default();
+    fonts
+        .font_data
+        .insert("cjk".to_string(), egui::FontData::from_owned(bytes).into());
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        fonts.families.entry(family).or_default().push("cjk".to_string());
+    }
+    ctx.set_fonts(fonts);
+}
+
+fn main() -> eframe::Result {
+    timeblock::utils::init_logging();
+    eframe::run_native(
+        "时光块 - 时间轴示例",
+        eframe::NativeOptions::default(),
+        Box::new(|cc| Ok(Box::new(TimelineApp::new(cc)))),
+    )
+}
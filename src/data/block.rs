@@ -1,10 +1,17 @@
 use std::fmt;
 use log::warn;
 
+use crate::data::duration::Duration;
 use crate::data::timeofday::TimeOfDay;
+use crate::utils::{self, Locale};
+
+/// 允许空名称时使用的占位名称
+pub const PLACEHOLDER_NAME: &str = "(未命名)";
+/// 休息预设的默认名称
+pub const BREAK_NAME: &str = "休息";
 
 /// 时间段块
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Block {
     /// 时间段的开始时间
      start_time: TimeOfDay,
@@ -16,14 +23,108 @@ pub struct Block {
      description: Option<String>,
     /// 标识该时间段是否为固定时间段
      is_fixed: bool,
+    /// 标识该时间段是否锁定（时间和内容都不可修改，用于只读来源导入的时间段）
+     locked: bool,
+    /// 优先级，数值越大越优先（默认 0）
+     priority: u8,
+    /// 标签，用于分类和着色
+     tags: Vec<String>,
+    /// 显示颜色（如 `#5b7db1`），未设置时由界面按是否固定选择默认颜色
+     color: Option<String>,
+    /// 描述的最大字符数，None 表示不限制；属于校验配置，不参与相等比较
+     max_description_len: Option<usize>,
+    /// 外部系统中的标识（如任务 ID、日历 UID），不要求唯一
+     id: Option<String>,
+}
+
+impl PartialEq for Block {
+    /// 比较时间段的内容和标记，忽略描述长度上限这类校验配置
+    fn eq(&self, other: &Block) -> bool {
+        let Block {
+            start_time,
+            end_time,
+            name,
+            description,
+            is_fixed,
+            locked,
+            priority,
+            tags,
+            color,
+            max_description_len: _,
+            id,
+        } = self;
+        *start_time == other.start_time
+            && *end_time == other.end_time
+            && *name == other.name
+            && *description == other.description
+            && *is_fixed == other.is_fixed
+            && *locked == other.locked
+            && *priority == other.priority
+            && *tags == other.tags
+            && *color == other.color
+            && *id == other.id
+    }
 }
 
+impl Eq for Block {}
+
 
 impl Block {
     /// 创建 BlockBuilder
     pub fn builder() -> BlockBuilder {
         BlockBuilder::new()
     }
+    /// 复制全部字段生成构建器，便于在表单中修改后重新构建
+    pub fn to_builder(&self) -> BlockBuilder {
+        Block::builder()
+            .start_time(self.start_time)
+            .end_time(self.end_time)
+            .name(self.name.clone())
+            .description(self.description.clone())
+            .is_fixed(self.is_fixed)
+            .locked(self.locked)
+            .priority(self.priority)
+            .tags(self.tags.clone())
+            .color(self.color.clone())
+            .max_description_len(self.max_description_len)
+            .id(self.id.clone())
+    }
+    /// 复制一份用于第二天（如“明天重复这个时间段”），起止时间、名称、描述和是否固定都保持不变
+    ///
+    /// 外部标识指向的是当天的那一次，不会被复制。
+    pub fn repeat_next_day(&self) -> Block {
+        Block {
+            id: None,
+            ..self.clone()
+        }
+    }
+    /// 预设：会议（固定时间段）
+    pub fn meeting(start_time: TimeOfDay, end_time: TimeOfDay, name: String) -> Result<Block, BlockError> {
+        Block::builder()
+            .start_time(start_time)
+            .end_time(end_time)
+            .name(name)
+            .is_fixed(true)
+            .build()
+    }
+    /// 预设：休息（默认名称，非固定）
+    pub fn break_time(start_time: TimeOfDay, end_time: TimeOfDay) -> Result<Block, BlockError> {
+        Block::builder()
+            .start_time(start_time)
+            .end_time(end_time)
+            .name(BREAK_NAME.to_string())
+            .is_fixed(false)
+            .build()
+    }
+    /// 预设：专注工作（非固定）
+    pub fn focus(start_time: TimeOfDay, end_time: TimeOfDay, name: String) -> Result<Block, BlockError> {
+        Block::builder()
+            .start_time(start_time)
+            .end_time(end_time)
+            .name(name)
+            .is_fixed(false)
+            .build()
+    }
     /// 获取开始时间
     pub fn start_time(&self) -> TimeOfDay {
         self.start_time
@@ -44,8 +145,35 @@ impl Block {
     pub fn is_fixed(&self) -> bool {
         self.is_fixed
     }
+    /// 获取是否锁定标识
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+    /// 获取优先级
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+    /// 获取标签
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    /// 获取显示颜色
+    pub fn color(&self) -> Option<&str> {
+        self.color.as_deref()
+    }
+    /// 获取描述的最大字符数，None 表示不限制
+    pub fn max_description_len(&self) -> Option<usize> {
+        self.max_description_len
+    }
+    /// 获取外部标识
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
     /// 修改时间段
     pub fn set_time(&mut self, start_time: TimeOfDay, end_time: TimeOfDay)->Result<(), BlockError>{
+        if self.locked{
+            return Err(BlockError::Locked);
+        }
         if start_time >= end_time{
             return Err(BlockError::InvalidTime { start: start_time, end: end_time });
         }
@@ -56,8 +184,31 @@ impl Block {
         self.end_time = end_time;
         Ok(())
     }
+    /// 直接设置起止时间，不检查锁定、固定和时间范围，仅供调用方已保证合法的内部批量操作使用
+    pub(crate) fn set_times_unchecked(&mut self, start_time: TimeOfDay, end_time: TimeOfDay) {
+        debug_assert!(start_time < end_time, "结束时间 {} 必须晚于开始时间 {}", end_time, start_time);
+        self.start_time = start_time;
+        self.end_time = end_time;
+    }
+    /// 将结束时间推后若干分钟
+    pub fn extend(&mut self, minutes: u16) -> Result<(), BlockError> {
+        let end_time = u16::from(self.end_time)
+            .checked_add(minutes)
+            .and_then(|end| TimeOfDay::try_from(end).ok())
+            .ok_or(BlockError::OutOfDay)?;
+        self.set_time(self.start_time, end_time)
+    }
+    /// 将结束时间提前若干分钟，时长不足 1 分钟时返回 `InvalidTime`
+    pub fn shrink(&mut self, minutes: u16) -> Result<(), BlockError> {
+        let end_minutes = u16::from(self.end_time).saturating_sub(minutes);
+        let end_time = TimeOfDay::try_from(end_minutes).expect("提前后的结束时间仍在一天之内");
+        self.set_time(self.start_time, end_time)
+    }
     /// 修改名称
     pub fn set_name(&mut self, name: String) -> Result<(),BlockError>{
+        if self.locked{
+            return Err(BlockError::Locked);
+        }
         if name.is_empty(){
             return Err(BlockError::EmptyName);
         }
@@ -65,19 +216,283 @@ impl Block {
         Ok(())
 
     }
-    /// 修改描述
-    pub fn set_description(&mut self, description: Option<String>){
+    /// 修改描述，去掉末尾空白后超过构建时设置的最大长度时返回错误
+    pub fn set_description(&mut self, description: Option<String>) -> Result<(), BlockError>{
+        if self.locked{
+            return Err(BlockError::Locked);
+        }
+        let description = trim_description(description);
+        check_description_len(description.as_deref(), self.max_description_len)?;
         self.description = description;
-
+        Ok(())
+    }
+    /// 按模板生成描述，支持 `{name}`、`{start}`、`{end}`、`{duration}`（分钟）占位符，未知占位符原样保留
+    ///
+    /// 例如 `"{name}：{start} 到 {end}"`。替换只进行一遍，名称中的花括号不会被再次展开。
+    pub fn apply_description_template(&mut self, template: &str) -> Result<(), BlockError> {
+        let mut description = String::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            description.push_str(&rest[..open]);
+            let tail = &rest[open..];
+            let Some(close) = tail.find('}') else {
+                rest = tail;
+                break;
+            };
+            match &tail[1..close] {
+                "name" => description.push_str(&self.name),
+                "start" => description.push_str(&self.start_time.to_string()),
+                "end" => description.push_str(&self.end_time.to_string()),
+                "duration" => description.push_str(&self.duration().minutes().to_string()),
+                _ => description.push_str(&tail[..=close]),
+            }
+            rest = &tail[close + 1..];
+        }
+        description.push_str(rest);
+        self.set_description(Some(description))
+    }
+    /// 修改优先级
+    pub fn set_priority(&mut self, priority: u8) -> Result<(), BlockError> {
+        if self.locked {
+            return Err(BlockError::Locked);
+        }
+        self.priority = priority;
+        Ok(())
+    }
+    /// 修改标签
+    pub fn set_tags(&mut self, tags: Vec<String>) -> Result<(), BlockError> {
+        if self.locked {
+            return Err(BlockError::Locked);
+        }
+        self.tags = tags;
+        Ok(())
+    }
+    /// 修改显示颜色（只影响显示，锁定的时间段也可修改）
+    pub fn set_color(&mut self, color: Option<String>) {
+        self.color = color;
+    }
+    /// 修改外部标识（只用于关联外部系统，锁定的时间段也可修改）
+    pub fn set_id(&mut self, id: Option<String>) {
+        self.id = id;
     }
     /// 修改是否固定
     pub fn set_is_fixed(&mut self, is_fixed: bool){
         self.is_fixed = is_fixed;
     }
+    /// 修改是否锁定
+    pub fn set_locked(&mut self, locked: bool){
+        self.locked = locked;
+    }
+    /// 获取持续时长
+    pub fn duration(&self) -> Duration {
+        self.end_time.duration_since(self.start_time).expect("结束时间晚于开始时间")
+    }
+    /// 判断时刻是否落在时间段内（包含开始时间，不包含结束时间）
+    pub fn contains(&self, time: TimeOfDay) -> bool {
+        self.start_time <= time && time < self.end_time
+    }
+    /// 判断两个时间段是否重叠（首尾相接不算重叠）
+    pub fn overlaps(&self, other: &Block) -> bool {
+        self.start_time < other.end_time && other.start_time < self.end_time
+    }
+    /// 两个时间段共同占用的分钟数，不重叠时为 0
+    pub fn overlap_minutes(&self, other: &Block) -> u16 {
+        let start = self.start_time.max(other.start_time);
+        let end = self.end_time.min(other.end_time);
+        u16::from(end).saturating_sub(u16::from(start))
+    }
+    /// 本时间段被 `other` 覆盖的比例：不重叠为 0.0，被完全覆盖为 1.0，用于判断冲突的轻重
+    pub fn overlap_fraction(&self, other: &Block) -> f32 {
+        f32::from(self.overlap_minutes(other)) / f32::from(self.duration().minutes())
+    }
+    /// 判断内容是否相同：只比较开始时间、结束时间、名称和描述，忽略是否固定、锁定和优先级等标记
+    ///
+    /// 用于对账导入的日历，导入来源往往不知道这些标记。
+    pub fn content_eq(&self, other: &Block) -> bool {
+        self.start_time == other.start_time
+            && self.end_time == other.end_time
+            && self.name == other.name
+            && self.description == other.description
+    }
+    /// 判断是否占据同一时段：开始时间、结束时间和名称都相同
+    pub fn same_slot(&self, other: &Block) -> bool {
+        self.start_time == other.start_time && self.end_time == other.end_time && self.name == other.name
+    }
+    /// 判断两个时间段是否首尾相接（任一方向），重叠不算相接
+    pub fn is_adjacent_to(&self, other: &Block) -> bool {
+        self.end_time == other.start_time || other.end_time == self.start_time
+    }
+    /// 将时间段裁剪到给定窗口内，完全落在窗口外时返回 None
+    pub fn trim_to(&self, window_start: TimeOfDay, window_end: TimeOfDay) -> Option<Block> {
+        let start_time = self.start_time.max(window_start);
+        let end_time = self.end_time.min(window_end);
+        if start_time >= end_time {
+            return None;
+        }
+        Some(Block {
+            start_time,
+            end_time,
+            ..self.clone()
+        })
+    }
+    /// 两个时间段的共同区间，命名为“甲 ∩ 乙”，其余字段取默认值；不重叠时返回 None
+    pub fn intersect(&self, other: &Block) -> Option<Block> {
+        let start_time = self.start_time.max(other.start_time);
+        let end_time = self.end_time.min(other.end_time);
+        if start_time >= end_time {
+            return None;
+        }
+        Some(Block {
+            start_time,
+            end_time,
+            name: format!("{} ∩ {}", self.name, other.name),
+            description: None,
+            is_fixed: false,
+            locked: false,
+            priority: 0,
+            tags: Vec::new(),
+            color: None,
+            max_description_len: None,
+            id: None,
+        })
+    }
+    /// 从自身扣除 `other` 覆盖的部分，返回剩余的 0 至 2 段，其余字段与自身相同
+    ///
+    /// 例如从工作时间段中挖出休息时间。不重叠时原样返回自身。
+    pub fn subtract(&self, other: &Block) -> Vec<Block> {
+        if !self.overlaps(other) {
+            return vec![self.clone()];
+        }
+        [(self.start_time, other.start_time), (other.end_time, self.end_time)]
+            .into_iter()
+            .filter(|(start_time, end_time)| start_time < end_time)
+            .map(|(start_time, end_time)| Block {
+                start_time,
+                end_time,
+                ..self.clone()
+            })
+            .collect()
+    }
+    /// 相对于 `now` 用自然语言描述时间段的状态，例如“15 分钟后开始”“进行中，还剩 20 分钟”“5 分钟前结束”
+    ///
+    /// 时间段为左闭右开区间：`now` 等于开始时间时为“进行中”，等于结束时间时为“刚刚结束”。
+    /// 语言跟随 `utils::locale()`。
+    pub fn describe_relative(&self, now: TimeOfDay) -> String {
+        let now = u16::from(now);
+        let start = u16::from(self.start_time);
+        let end = u16::from(self.end_time);
+        let locale = utils::locale();
+        if now < start {
+            match locale {
+                Locale::ZhCn => format!("{} 分钟后开始", start - now),
+                Locale::En => format!("starts in {} minutes", start - now),
+            }
+        } else if now < end {
+            match locale {
+                Locale::ZhCn => format!("进行中，还剩 {} 分钟", end - now),
+                Locale::En => format!("in progress, {} minutes left", end - now),
+            }
+        } else if now == end {
+            match locale {
+                Locale::ZhCn => "刚刚结束".to_string(),
+                Locale::En => "just ended".to_string(),
+            }
+        } else {
+            match locale {
+                Locale::ZhCn => format!("{} 分钟前结束", now - end),
+                Locale::En => format!("ended {} minutes ago", now - end),
+            }
+        }
+    }
+
+
+}
 
 
+impl fmt::Display for Block {
+    /// 显示格式与 `Schedule::from_text` 的行格式一致：`09:00-10:30 会议 | 项目讨论`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{} {}", self.start_time, self.end_time, self.name)?;
+        if let Some(description) = &self.description {
+            write!(f, " | {}", description)?;
+        }
+        Ok(())
+    }
 }
 
+/// 没有结束时间的时间段（如“14:00 起研究”），视为持续到一天结束（23:59）
+///
+/// 与 `Block` 分开表示，普通时间段“结束时间晚于开始时间”的约束不受影响；
+/// 需要放入日程时用 `close` 转为结束于 23:59 的普通时间段。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenBlock {
+    /// 开始时间
+    start_time: TimeOfDay,
+    /// 名称
+    name: String,
+    /// 可选的描述
+    description: Option<String>,
+    /// 是否为固定时间段
+    is_fixed: bool,
+}
+
+impl OpenBlock {
+    /// 创建开放式时间段，名称为空或开始时间不早于 23:59 时返回错误
+    pub fn new(start_time: TimeOfDay, name: String, is_fixed: bool) -> Result<OpenBlock, BlockError> {
+        if name.is_empty() {
+            return Err(BlockError::EmptyName);
+        }
+        if start_time >= TimeOfDay::END_OF_DAY {
+            return Err(BlockError::InvalidTime { start: start_time, end: TimeOfDay::END_OF_DAY });
+        }
+        Ok(OpenBlock { start_time, name, description: None, is_fixed })
+    }
+    /// 设置描述
+    pub fn with_description(mut self, description: Option<String>) -> OpenBlock {
+        self.description = description;
+        self
+    }
+    /// 获取开始时间
+    pub fn start_time(&self) -> TimeOfDay {
+        self.start_time
+    }
+    /// 获取名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// 获取描述
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    /// 获取是否固定标识
+    pub fn is_fixed(&self) -> bool {
+        self.is_fixed
+    }
+    /// 获取持续时长，按持续到 23:59 计算
+    pub fn duration(&self) -> Duration {
+        TimeOfDay::END_OF_DAY.duration_since(self.start_time).expect("开始时间早于 23:59")
+    }
+    /// 判断是否与普通时间段重叠（开放的结尾视为 23:59）
+    pub fn overlaps(&self, other: &Block) -> bool {
+        self.start_time < other.end_time && other.start_time < TimeOfDay::END_OF_DAY
+    }
+    /// 转为结束于 23:59 的普通时间段
+    pub fn close(&self) -> Block {
+        Block {
+            start_time: self.start_time,
+            end_time: TimeOfDay::END_OF_DAY,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            is_fixed: self.is_fixed,
+            locked: false,
+            priority: 0,
+            tags: Vec::new(),
+            color: None,
+            max_description_len: None,
+            id: None,
+        }
+    }
+}
 
 /// Block 构建器
 pub struct BlockBuilder {
@@ -91,6 +506,28 @@ pub struct BlockBuilder {
     description: Option<Option<String>>,
     /// 可选的是否固定标识
     is_fixed: Option<bool>,
+    /// 是否锁定（默认不锁定）
+    locked: bool,
+    /// 优先级（默认 0）
+    priority: u8,
+    /// 标签（默认无）
+    tags: Vec<String>,
+    /// 显示颜色（默认不设置）
+    color: Option<String>,
+    /// 描述的最大字符数（默认不限制）
+    max_description_len: Option<usize>,
+    /// 外部标识（默认无）
+    id: Option<String>,
+    /// 可选的允许时间窗口（如工作时间）
+    within: Option<(TimeOfDay, TimeOfDay)>,
+    /// 非固定时间段是否允许空名称（以占位名称代替）
+    allow_empty_name: bool,
+}
+
+impl Default for BlockBuilder {
+    fn default() -> BlockBuilder {
+        BlockBuilder::new()
+    }
 }
 
 impl BlockBuilder { 
@@ -102,6 +539,14 @@ impl BlockBuilder {
             name: None,
             description: None,
             is_fixed: None,
+            locked: false,
+            priority: 0,
+            tags: Vec::new(),
+            color: None,
+            max_description_len: None,
+            id: None,
+            within: None,
+            allow_empty_name: false,
         }
     }
 
@@ -135,20 +580,83 @@ impl BlockBuilder {
         self
     }
 
+    /// 设置是否锁定
+    pub fn locked(mut self, locked: bool) -> BlockBuilder {
+        self.locked = locked;
+        self
+    }
+
+    /// 设置优先级
+    pub fn priority(mut self, priority: u8) -> BlockBuilder {
+        self.priority = priority;
+        self
+    }
+
+    /// 设置标签
+    pub fn tags(mut self, tags: Vec<String>) -> BlockBuilder {
+        self.tags = tags;
+        self
+    }
+
+    /// 设置显示颜色
+    pub fn color(mut self, color: Option<String>) -> BlockBuilder {
+        self.color = color;
+        self
+    }
+
+    /// 限制描述的最大字符数，之后 `set_description` 也按此校验
+    pub fn max_description_len(mut self, max: Option<usize>) -> BlockBuilder {
+        self.max_description_len = max;
+        self
+    }
+
+    /// 设置外部标识
+    pub fn id(mut self, id: Option<String>) -> BlockBuilder {
+        self.id = id;
+        self
+    }
+
+    /// 限制时间段必须落在 [start, end] 窗口内（如工作时间）
+    pub fn within(mut self, start: TimeOfDay, end: TimeOfDay) -> BlockBuilder {
+        self.within = Some((start, end));
+        self
+    }
+
+    /// 允许非固定时间段使用空名称，构建时以占位名称代替并记录警告
+    pub fn allow_empty_name(mut self, allow: bool) -> BlockBuilder {
+        self.allow_empty_name = allow;
+        self
+    }
+
     /// 构建 Block 实例
     pub fn build(self) -> Result<Block, BlockError> { 
         let start_time = self.start_time.ok_or(BlockError::MissingRequiredField("start_time"))?;
         let end_time = self.end_time.ok_or(BlockError::MissingRequiredField("end_time"))?;
-        let name = self.name.ok_or(BlockError::MissingRequiredField("name"))?;
-        let description = self.description.unwrap_or(None);
+        let mut name = self.name.ok_or(BlockError::MissingRequiredField("name"))?;
+        let description = trim_description(self.description.unwrap_or(None));
+        check_description_len(description.as_deref(), self.max_description_len)?;
         let is_fixed = self.is_fixed.unwrap_or({
             warn!("未指定是否固定，默认为非固定");
             false
         });
+        // 验证名称
+        if name.is_empty() {
+            if !self.allow_empty_name || is_fixed {
+                return Err(BlockError::EmptyName);
+            }
+            warn!("名称为空，使用占位名称 {}", PLACEHOLDER_NAME);
+            name = PLACEHOLDER_NAME.to_string();
+        }
         // 验证时间范围
         if start_time >= end_time {
             return Err(BlockError::InvalidTime { start: start_time, end: end_time });
         }
+        // 验证时间窗口
+        if let Some((window_start, window_end)) = self.within
+            && (start_time < window_start || end_time > window_end)
+        {
+            return Err(BlockError::OutsideWorkingHours { start: window_start, end: window_end });
+        }
 
         Ok(Block {
             start_time,
@@ -156,8 +664,87 @@ impl BlockBuilder {
             name,
             description,
             is_fixed,
+            locked: self.locked,
+            priority: self.priority,
+            tags: self.tags,
+            color: self.color,
+            max_description_len: self.max_description_len,
+            id: self.id,
         })
     }
+
+    /// 构建 Block 实例，一次性收集所有错误（便于表单同时标出每个问题）
+    pub fn build_checked(self) -> Result<Block, Vec<BlockError>> {
+        let mut errors = Vec::new();
+        if self.start_time.is_none() {
+            errors.push(BlockError::MissingRequiredField("start_time"));
+        }
+        if self.end_time.is_none() {
+            errors.push(BlockError::MissingRequiredField("end_time"));
+        }
+        match &self.name {
+            None => errors.push(BlockError::MissingRequiredField("name")),
+            Some(name) if name.is_empty() && (!self.allow_empty_name || self.is_fixed == Some(true)) => {
+                errors.push(BlockError::EmptyName)
+            }
+            Some(_) => {}
+        }
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time)
+            && start >= end
+        {
+            errors.push(BlockError::InvalidTime { start, end });
+        }
+        if let (Some(start), Some(end), Some((window_start, window_end))) = (self.start_time, self.end_time, self.within)
+            && (start < window_start || end > window_end)
+        {
+            errors.push(BlockError::OutsideWorkingHours { start: window_start, end: window_end });
+        }
+        if let Some(Some(description)) = &self.description
+            && let Err(e) = check_description_len(Some(description.trim_end()), self.max_description_len)
+        {
+            errors.push(e);
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        self.build().map_err(|e| vec![e])
+    }
+}
+
+/// 为可能相互重叠的时间段分配并排显示的列（从 0 开始），同一列中的时间段互不重叠，且列数最少
+///
+/// 按开始时间依次放入编号最小的已空出的列，没有时新开一列；返回值与输入一一对应。
+pub fn assign_lanes(blocks: &[Block]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..blocks.len()).collect();
+    order.sort_by_key(|&i| (blocks[i].start_time, blocks[i].end_time));
+    let mut lane_ends: Vec<TimeOfDay> = Vec::new();
+    let mut lanes = vec![0; blocks.len()];
+    for i in order {
+        let block = &blocks[i];
+        let lane = match lane_ends.iter().position(|&end| end <= block.start_time) {
+            Some(lane) => lane,
+            None => {
+                lane_ends.push(block.end_time);
+                lane_ends.len() - 1
+            }
+        };
+        lane_ends[lane] = block.end_time;
+        lanes[i] = lane;
+    }
+    lanes
+}
+
+/// 去掉描述末尾的空白
+fn trim_description(description: Option<String>) -> Option<String> {
+    description.map(|d| d.trim_end().to_string())
+}
+
+/// 检查描述是否超过最大字符数
+fn check_description_len(description: Option<&str>, max: Option<usize>) -> Result<(), BlockError> {
+    match (description.map(|d| d.chars().count()), max) {
+        (Some(actual), Some(max)) if actual > max => Err(BlockError::DescriptionTooLong { max, actual }),
+        _ => Ok(()),
+    }
 }
 
 /// 表示构建 `Block` 时可能发生的错误。
@@ -170,24 +757,84 @@ pub enum BlockError {
     /// 固定时间段不能修改时间范围
     FixedBlockTimeChange,
     EmptyName,
+    /// 锁定的时间段不能修改
+    Locked,
+    /// 时间超出一天的范围
+    OutOfDay,
+    /// 时间段超出允许的时间窗口
+    OutsideWorkingHours { start: TimeOfDay, end: TimeOfDay },
+    /// 描述超过最大字符数
+    DescriptionTooLong { max: usize, actual: usize },
+}
+
+impl BlockError {
+    /// 稳定的机器可读错误码，便于调用方映射到自己的本地化文案
+    pub fn code(&self) -> &'static str {
+        match self {
+            BlockError::InvalidTime { .. } => "invalid_time",
+            BlockError::MissingRequiredField(_) => "missing_required_field",
+            BlockError::FixedBlockTimeChange => "fixed_block_time_change",
+            BlockError::EmptyName => "empty_name",
+            BlockError::Locked => "locked",
+            BlockError::OutOfDay => "out_of_day",
+            BlockError::OutsideWorkingHours { .. } => "outside_working_hours",
+            BlockError::DescriptionTooLong { .. } => "description_too_long",
+        }
+    }
 }
 
 impl fmt::Display for BlockError {
-    /// 格式化错误信息
+    /// 按当前语言格式化错误信息
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            BlockError::InvalidTime { start, end } => {
+        match (self, utils::locale()) {
+            (BlockError::InvalidTime { start, end }, Locale::ZhCn) => {
                 write!(f, "时间范围：结束时间 {} 必须晚于开始时间 {}", end, start)
             }
-            BlockError::MissingRequiredField(field) => {
+            (BlockError::InvalidTime { start, end }, Locale::En) => {
+                write!(f, "Invalid time range: end {} must be after start {}", end, start)
+            }
+            (BlockError::MissingRequiredField(field), Locale::ZhCn) => {
                 write!(f, "缺少必需字段：{}", field)
             }
-            BlockError::FixedBlockTimeChange => {
+            (BlockError::MissingRequiredField(field), Locale::En) => {
+                write!(f, "Missing required field: {}", field)
+            }
+            (BlockError::FixedBlockTimeChange, Locale::ZhCn) => {
                 write!(f, "固定时间段不能修改时间范围")
             }
-            BlockError::EmptyName   => {
+            (BlockError::FixedBlockTimeChange, Locale::En) => {
+                write!(f, "Cannot change the time of a fixed block")
+            }
+            (BlockError::EmptyName, Locale::ZhCn) => {
                 write!(f, "名称不能为空!")
             }
+            (BlockError::EmptyName, Locale::En) => {
+                write!(f, "Name must not be empty")
+            }
+            (BlockError::Locked, Locale::ZhCn) => {
+                write!(f, "时间段已锁定，不能修改")
+            }
+            (BlockError::Locked, Locale::En) => {
+                write!(f, "Block is locked and cannot be modified")
+            }
+            (BlockError::OutOfDay, Locale::ZhCn) => {
+                write!(f, "时间超出一天的范围")
+            }
+            (BlockError::OutOfDay, Locale::En) => {
+                write!(f, "Time is outside of the day")
+            }
+            (BlockError::OutsideWorkingHours { start, end }, Locale::ZhCn) => {
+                write!(f, "时间段必须位于 {}-{} 之内", start, end)
+            }
+            (BlockError::OutsideWorkingHours { start, end }, Locale::En) => {
+                write!(f, "Block must be within {}-{}", start, end)
+            }
+            (BlockError::DescriptionTooLong { max, actual }, Locale::ZhCn) => {
+                write!(f, "描述过长：{} 个字符，最多 {} 个", actual, max)
+            }
+            (BlockError::DescriptionTooLong { max, actual }, Locale::En) => {
+                write!(f, "Description is too long: {} characters, at most {}", actual, max)
+            }
         }
     }
 }
@@ -220,6 +867,79 @@ mod tests {
         assert_eq!(block.is_fixed, true);
     }
 
+    #[test]
+    fn test_to_builder_round_trip() {
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 30).unwrap())
+            .name("会议".to_string())
+            .description(Some("项目讨论".to_string()))
+            .is_fixed(true)
+            .locked(true)
+            .priority(3)
+            .tags(vec!["工作".to_string()])
+            .color(Some("#5b7db1".to_string()))
+            .id(Some("task-42".to_string()))
+            .build()
+            .unwrap();
+        assert_eq!(block.to_builder().build().unwrap(), block);
+
+        let renamed = block.to_builder().name("周会".to_string()).build().unwrap();
+        assert_eq!(renamed.name(), "周会");
+        assert_eq!(renamed.priority(), 3);
+    }
+
+    #[test]
+    fn test_description_max_len() {
+        let builder = || {
+            Block::builder()
+                .start_time(TimeOfDay::new(9, 0).unwrap())
+                .end_time(TimeOfDay::new(10, 0).unwrap())
+                .name("会议".to_string())
+                .is_fixed(false)
+        };
+        // 默认不限制
+        let long = "议".repeat(1000);
+        let block = builder().description(Some(long.clone())).build().unwrap();
+        assert_eq!(block.description(), Some(long.as_str()));
+
+        // 超出上限，按字符计数
+        let result = builder().max_description_len(Some(4)).description(Some("项目讨论会".to_string())).build();
+        assert!(matches!(result, Err(BlockError::DescriptionTooLong { max: 4, actual: 5 })));
+        let result = builder().max_description_len(Some(4)).description(Some("项目讨论会".to_string())).build_checked();
+        assert!(matches!(result.unwrap_err()[..], [BlockError::DescriptionTooLong { .. }]));
+
+        // set_description 沿用构建时的上限，末尾空白不计入
+        let mut block = builder().max_description_len(Some(4)).build().unwrap();
+        block.set_description(Some("项目讨论  \n".to_string())).unwrap();
+        assert_eq!(block.description(), Some("项目讨论"));
+        let result = block.set_description(Some("项目讨论会".to_string()));
+        assert!(matches!(result, Err(BlockError::DescriptionTooLong { max: 4, actual: 5 })));
+        assert_eq!(block.description(), Some("项目讨论"));
+        assert_eq!(block.max_description_len(), Some(4));
+
+        // 上限不参与相等比较
+        let plain = builder().description(Some("项目讨论".to_string())).build().unwrap();
+        assert_eq!(block, plain);
+    }
+
+    #[test]
+    fn test_repeat_next_day() {
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(7, 0).unwrap())
+            .end_time(TimeOfDay::new(7, 30).unwrap())
+            .name("晨跑".to_string())
+            .description(Some("河边".to_string()))
+            .is_fixed(true)
+            .id(Some("habit-1".to_string()))
+            .build()
+            .unwrap();
+        let tomorrow = block.repeat_next_day();
+        assert!(tomorrow.content_eq(&block));
+        assert!(tomorrow.is_fixed());
+        assert_eq!(tomorrow.id(), None);
+    }
+
     #[test]
     fn test_block_builder_minimal() {
         let start = TimeOfDay::new(14, 0).unwrap();
@@ -456,15 +1176,15 @@ mod tests {
             .unwrap();
 
         // 修改描述
-        block.set_description(Some("新描述".to_string()));
+        block.set_description(Some("新描述".to_string())).unwrap();
         assert_eq!(block.description(), Some("新描述"));
         
         // 清空描述
-        block.set_description(None);
+        block.set_description(None).unwrap();
         assert_eq!(block.description(), None);
         
         // 重新设置描述
-        block.set_description(Some("再次设置".to_string()));
+        block.set_description(Some("再次设置".to_string())).unwrap();
         assert_eq!(block.description(), Some("再次设置"));
     }
 
@@ -502,7 +1222,7 @@ mod tests {
         
         // 修改名称和描述
         block.set_name("重要会议".to_string()).unwrap();
-        block.set_description(Some("项目讨论".to_string()));
+        block.set_description(Some("项目讨论".to_string())).unwrap();
         
         // 设为固定
         block.set_is_fixed(true);
@@ -518,4 +1238,591 @@ mod tests {
         let result = block.set_time(TimeOfDay::new(12, 0).unwrap(), TimeOfDay::new(13, 0).unwrap());
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_duration_and_overlaps() {
+        let a = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 30).unwrap())
+            .name("会议".to_string())
+            .build()
+            .unwrap();
+        let b = Block::builder()
+            .start_time(TimeOfDay::new(10, 0).unwrap())
+            .end_time(TimeOfDay::new(11, 0).unwrap())
+            .name("讨论".to_string())
+            .build()
+            .unwrap();
+        let c = Block::builder()
+            .start_time(TimeOfDay::new(10, 30).unwrap())
+            .end_time(TimeOfDay::new(11, 0).unwrap())
+            .name("相接".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(a.duration(), Duration::from_minutes(90));
+        assert!(a.overlaps(&b));
+        assert!(b.overlaps(&a));
+        // 首尾相接不算重叠
+        assert!(!a.overlaps(&c));
+    }
+
+    #[test]
+    fn test_build_checked_collects_all_errors() {
+        let errors = Block::builder()
+            .description(Some("只有描述".to_string()))
+            .build_checked()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|e| matches!(e, BlockError::MissingRequiredField("start_time"))));
+        assert!(errors.iter().any(|e| matches!(e, BlockError::MissingRequiredField("end_time"))));
+        assert!(errors.iter().any(|e| matches!(e, BlockError::MissingRequiredField("name"))));
+
+        // 缺少名称且时间范围无效
+        let errors = Block::builder()
+            .start_time(TimeOfDay::new(11, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .build_checked()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| matches!(e, BlockError::MissingRequiredField("name"))));
+        assert!(errors.iter().any(|e| matches!(e, BlockError::InvalidTime { .. })));
+    }
+
+    #[test]
+    fn test_build_checked_success() {
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .name("会议".to_string())
+            .is_fixed(false)
+            .build_checked()
+            .expect("应该成功创建 Block");
+
+        assert_eq!(block.name(), "会议");
+    }
+
+    #[test]
+    fn test_trim_to() {
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(11, 0).unwrap())
+            .name("会议".to_string())
+            .description(Some("项目讨论".to_string()))
+            .is_fixed(true)
+            .build()
+            .unwrap();
+
+        // 完全在窗口内：保持不变
+        let inside = block.trim_to(TimeOfDay::new(8, 0).unwrap(), TimeOfDay::new(12, 0).unwrap());
+        assert_eq!(inside, Some(block.clone()));
+
+        // 部分重叠：裁剪到窗口边界，其余字段保留
+        let clipped = block
+            .trim_to(TimeOfDay::new(10, 0).unwrap(), TimeOfDay::new(12, 0).unwrap())
+            .expect("应该裁剪出 10:00-11:00");
+        assert_eq!(clipped.start_time(), TimeOfDay::new(10, 0).unwrap());
+        assert_eq!(clipped.end_time(), TimeOfDay::new(11, 0).unwrap());
+        assert_eq!(clipped.name(), "会议");
+        assert_eq!(clipped.description(), Some("项目讨论"));
+        assert!(clipped.is_fixed());
+
+        // 完全在窗口外（包括首尾相接）
+        assert!(block.trim_to(TimeOfDay::new(12, 0).unwrap(), TimeOfDay::new(13, 0).unwrap()).is_none());
+        assert!(block.trim_to(TimeOfDay::new(11, 0).unwrap(), TimeOfDay::new(12, 0).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_error_code() {
+        let time = TimeOfDay::new(9, 0).unwrap();
+        assert_eq!(BlockError::InvalidTime { start: time, end: time }.code(), "invalid_time");
+        assert_eq!(BlockError::MissingRequiredField("name").code(), "missing_required_field");
+        assert_eq!(BlockError::FixedBlockTimeChange.code(), "fixed_block_time_change");
+        assert_eq!(BlockError::EmptyName.code(), "empty_name");
+    }
+
+    #[test]
+    fn test_is_adjacent_to() {
+        let make = |start: (u16, u16), end: (u16, u16)| {
+            Block::builder()
+                .start_time(TimeOfDay::new(start.0, start.1).unwrap())
+                .end_time(TimeOfDay::new(end.0, end.1).unwrap())
+                .name("测试".to_string())
+                .build()
+                .unwrap()
+        };
+        let a = make((9, 0), (10, 0));
+
+        // 首尾相接（两个方向）
+        let b = make((10, 0), (11, 0));
+        assert!(a.is_adjacent_to(&b));
+        assert!(b.is_adjacent_to(&a));
+
+        // 相隔 1 分钟
+        let c = make((10, 1), (11, 0));
+        assert!(!a.is_adjacent_to(&c));
+
+        // 重叠不算相接
+        let d = make((9, 30), (10, 30));
+        assert!(!a.is_adjacent_to(&d));
+    }
+
+    #[test]
+    fn test_builder_within_working_hours() {
+        let make = |start: (u16, u16), end: (u16, u16)| {
+            Block::builder()
+                .start_time(TimeOfDay::new(start.0, start.1).unwrap())
+                .end_time(TimeOfDay::new(end.0, end.1).unwrap())
+                .name("工作".to_string())
+                .is_fixed(false)
+                .within(TimeOfDay::new(8, 0).unwrap(), TimeOfDay::new(18, 0).unwrap())
+                .build()
+        };
+
+        // 窗口内（含恰好贴边）
+        assert!(make((8, 0), (18, 0)).is_ok());
+        assert!(make((9, 0), (10, 0)).is_ok());
+        // 开始早于窗口
+        assert!(matches!(make((7, 30), (9, 0)), Err(BlockError::OutsideWorkingHours { .. })));
+        // 结束晚于窗口
+        assert!(matches!(make((17, 0), (18, 30)), Err(BlockError::OutsideWorkingHours { .. })));
+        assert_eq!(make((17, 0), (18, 30)).unwrap_err().code(), "outside_working_hours");
+    }
+
+    #[test]
+    fn test_block_display() {
+        let mut block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 30).unwrap())
+            .name("会议".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(format!("{}", block), "09:00-10:30 会议");
+
+        block.set_description(Some("项目讨论".to_string())).unwrap();
+        assert_eq!(format!("{}", block), "09:00-10:30 会议 | 项目讨论");
+    }
+
+    #[test]
+    fn test_allow_empty_name() {
+        let make = || {
+            Block::builder()
+                .start_time(TimeOfDay::new(9, 0).unwrap())
+                .end_time(TimeOfDay::new(10, 0).unwrap())
+                .name(String::new())
+        };
+
+        // 默认严格：空名称报错
+        assert!(matches!(make().is_fixed(false).build(), Err(BlockError::EmptyName)));
+
+        // 开启后以占位名称代替
+        let block = make().is_fixed(false).allow_empty_name(true).build().unwrap();
+        assert_eq!(block.name(), PLACEHOLDER_NAME);
+
+        // 固定时间段仍然必须有名称
+        assert!(matches!(make().is_fixed(true).allow_empty_name(true).build(), Err(BlockError::EmptyName)));
+    }
+
+    #[test]
+    fn test_same_slot() {
+        let make = |name: &str, description: Option<&str>| {
+            Block::builder()
+                .start_time(TimeOfDay::new(9, 0).unwrap())
+                .end_time(TimeOfDay::new(10, 0).unwrap())
+                .name(name.to_string())
+                .description(description.map(str::to_string))
+                .build()
+                .unwrap()
+        };
+
+        assert!(make("会议", None).same_slot(&make("会议", Some("项目讨论"))));
+        assert!(!make("会议", None).same_slot(&make("讨论", None)));
+    }
+
+    #[test]
+    fn test_presets() {
+        let start = TimeOfDay::new(9, 0).unwrap();
+        let end = TimeOfDay::new(10, 0).unwrap();
+
+        let meeting = Block::meeting(start, end, "周会".to_string()).unwrap();
+        assert_eq!(meeting.name(), "周会");
+        assert!(meeting.is_fixed());
+        assert_eq!(meeting.description(), None);
+
+        let rest = Block::break_time(start, end).unwrap();
+        assert_eq!(rest.name(), BREAK_NAME);
+        assert!(!rest.is_fixed());
+
+        let focus = Block::focus(start, end, "写代码".to_string()).unwrap();
+        assert_eq!(focus.name(), "写代码");
+        assert!(!focus.is_fixed());
+
+        // 预设同样校验时间范围
+        assert!(matches!(Block::focus(end, start, "反了".to_string()), Err(BlockError::InvalidTime { .. })));
+    }
+
+    #[test]
+    fn test_overlap_minutes() {
+        let make = |start: (u16, u16), end: (u16, u16)| {
+            Block::builder()
+                .start_time(TimeOfDay::new(start.0, start.1).unwrap())
+                .end_time(TimeOfDay::new(end.0, end.1).unwrap())
+                .name("测试".to_string())
+                .build()
+                .unwrap()
+        };
+        let a = make((9, 0), (11, 0));
+
+        // 部分重叠
+        assert_eq!(a.overlap_minutes(&make((10, 30), (12, 0))), 30);
+        // 包含关系
+        assert_eq!(a.overlap_minutes(&make((9, 15), (9, 45))), 30);
+        assert_eq!(make((9, 15), (9, 45)).overlap_minutes(&a), 30);
+        // 不相交（含首尾相接）
+        assert_eq!(a.overlap_minutes(&make((12, 0), (13, 0))), 0);
+        assert_eq!(a.overlap_minutes(&make((11, 0), (13, 0))), 0);
+    }
+
+    #[test]
+    fn test_overlap_fraction() {
+        let make = |start: (u16, u16), end: (u16, u16)| {
+            Block::builder()
+                .start_time(TimeOfDay::new(start.0, start.1).unwrap())
+                .end_time(TimeOfDay::new(end.0, end.1).unwrap())
+                .name("测试".to_string())
+                .build()
+                .unwrap()
+        };
+        let a = make((9, 0), (11, 0));
+
+        // 不相交（含首尾相接）
+        assert_eq!(a.overlap_fraction(&make((11, 0), (12, 0))), 0.0);
+        // 部分重叠，比例相对于自身
+        assert_eq!(a.overlap_fraction(&make((10, 30), (12, 0))), 0.25);
+        assert_eq!(make((10, 30), (12, 0)).overlap_fraction(&a), 1.0 / 3.0);
+        // 包含关系
+        assert_eq!(a.overlap_fraction(&make((8, 0), (12, 0))), 1.0);
+        assert_eq!(a.overlap_fraction(&make((9, 30), (10, 0))), 0.25);
+    }
+
+    #[test]
+    fn test_locked_block_rejects_mutators() {
+        let mut block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .name("导入的会议".to_string())
+            .is_fixed(false)
+            .locked(true)
+            .build()
+            .unwrap();
+        assert!(block.is_locked());
+
+        let new_start = TimeOfDay::new(11, 0).unwrap();
+        let new_end = TimeOfDay::new(12, 0).unwrap();
+        assert!(matches!(block.set_time(new_start, new_end), Err(BlockError::Locked)));
+        assert!(matches!(block.set_name("改名".to_string()), Err(BlockError::Locked)));
+        assert!(matches!(block.set_description(Some("改描述".to_string())), Err(BlockError::Locked)));
+        assert_eq!(block.start_time(), TimeOfDay::new(9, 0).unwrap());
+        assert_eq!(block.name(), "导入的会议");
+        assert_eq!(block.description(), None);
+
+        // 解锁后恢复可编辑
+        block.set_locked(false);
+        block.set_time(new_start, new_end).unwrap();
+        block.set_name("改名".to_string()).unwrap();
+        block.set_description(Some("改描述".to_string())).unwrap();
+        assert_eq!(block.start_time(), new_start);
+        assert_eq!(block.name(), "改名");
+        assert_eq!(block.description(), Some("改描述"));
+    }
+
+    #[test]
+    fn test_extend_and_shrink() {
+        let mut block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .name("会议".to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap();
+
+        block.extend(30).unwrap();
+        assert_eq!(block.end_time(), TimeOfDay::new(10, 30).unwrap());
+
+        block.shrink(89).unwrap();
+        assert_eq!(block.end_time(), TimeOfDay::new(9, 1).unwrap());
+        assert_eq!(block.duration(), Duration::from_minutes(1));
+
+        // 缩短到 0 分钟或更短
+        assert!(matches!(block.shrink(1), Err(BlockError::InvalidTime { .. })));
+        assert!(matches!(block.shrink(600), Err(BlockError::InvalidTime { .. })));
+        assert_eq!(block.end_time(), TimeOfDay::new(9, 1).unwrap());
+
+        // 延长超出一天
+        assert!(matches!(block.extend(24 * 60), Err(BlockError::OutOfDay)));
+    }
+
+    #[test]
+    fn test_extend_and_shrink_fixed_block() {
+        let mut block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .name("固定会议".to_string())
+            .is_fixed(true)
+            .build()
+            .unwrap();
+
+        assert!(matches!(block.extend(30), Err(BlockError::FixedBlockTimeChange)));
+        assert!(matches!(block.shrink(30), Err(BlockError::FixedBlockTimeChange)));
+        assert_eq!(block.end_time(), TimeOfDay::new(10, 0).unwrap());
+    }
+
+    #[test]
+    fn test_describe_relative() {
+        let _locale = utils::lock_locale();
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .name("会议".to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap();
+        let t = |h, m| TimeOfDay::new(h, m).unwrap();
+
+        assert_eq!(block.describe_relative(t(8, 45)), "15 分钟后开始");
+        assert_eq!(block.describe_relative(t(8, 59)), "1 分钟后开始");
+        // 恰好开始
+        assert_eq!(block.describe_relative(t(9, 0)), "进行中，还剩 60 分钟");
+        assert_eq!(block.describe_relative(t(9, 40)), "进行中，还剩 20 分钟");
+        // 恰好结束
+        assert_eq!(block.describe_relative(t(10, 0)), "刚刚结束");
+        assert_eq!(block.describe_relative(t(10, 5)), "5 分钟前结束");
+    }
+
+    #[test]
+    fn test_contains() {
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .name("会议".to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap();
+        assert!(block.contains(TimeOfDay::new(9, 0).unwrap()));
+        assert!(block.contains(TimeOfDay::new(9, 59).unwrap()));
+        assert!(!block.contains(TimeOfDay::new(10, 0).unwrap()));
+        assert!(!block.contains(TimeOfDay::new(8, 59).unwrap()));
+    }
+
+    #[test]
+    fn test_error_display_locale() {
+        let _locale = utils::lock_locale();
+        let error = BlockError::Locked;
+        utils::set_locale(Locale::En);
+        assert_eq!(error.to_string(), "Block is locked and cannot be modified");
+        utils::set_locale(Locale::ZhCn);
+        assert_eq!(error.to_string(), "时间段已锁定，不能修改");
+    }
+
+    #[test]
+    fn test_describe_relative_locale() {
+        let _locale = utils::lock_locale();
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .name("会议".to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap();
+        utils::set_locale(Locale::En);
+        assert_eq!(block.describe_relative(TimeOfDay::new(8, 45).unwrap()), "starts in 15 minutes");
+        assert_eq!(block.describe_relative(TimeOfDay::new(9, 40).unwrap()), "in progress, 20 minutes left");
+        assert_eq!(block.describe_relative(TimeOfDay::new(10, 5).unwrap()), "ended 5 minutes ago");
+        utils::set_locale(Locale::ZhCn);
+        assert_eq!(block.describe_relative(TimeOfDay::new(8, 45).unwrap()), "15 分钟后开始");
+    }
+
+    #[test]
+    fn test_intersect() {
+        let block = |start: (u16, u16), end: (u16, u16), name: &str| {
+            Block::builder()
+                .start_time(TimeOfDay::new(start.0, start.1).unwrap())
+                .end_time(TimeOfDay::new(end.0, end.1).unwrap())
+                .name(name.to_string())
+                .description(Some("描述".to_string()))
+                .is_fixed(true)
+                .build()
+                .unwrap()
+        };
+        let a = block((9, 0), (11, 0), "甲");
+
+        // 部分重叠
+        let shared = a.intersect(&block((10, 0), (12, 0), "乙")).unwrap();
+        assert_eq!(shared.start_time(), TimeOfDay::new(10, 0).unwrap());
+        assert_eq!(shared.end_time(), TimeOfDay::new(11, 0).unwrap());
+        assert_eq!(shared.name(), "甲 ∩ 乙");
+        assert_eq!(shared.description(), None);
+        assert!(!shared.is_fixed());
+
+        // 包含
+        let shared = a.intersect(&block((9, 30), (10, 0), "丙")).unwrap();
+        assert_eq!(shared.start_time(), TimeOfDay::new(9, 30).unwrap());
+        assert_eq!(shared.end_time(), TimeOfDay::new(10, 0).unwrap());
+
+        // 不相交（首尾相接也不算）
+        assert!(a.intersect(&block((11, 0), (12, 0), "丁")).is_none());
+        assert!(a.intersect(&block((13, 0), (14, 0), "戊")).is_none());
+    }
+
+    #[test]
+    fn test_subtract() {
+        let work = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(12, 0).unwrap())
+            .name("写代码".to_string())
+            .description(Some("重构".to_string()))
+            .is_fixed(false)
+            .build()
+            .unwrap();
+        let cut = |start: (u16, u16), end: (u16, u16)| {
+            let other = Block::focus(
+                TimeOfDay::new(start.0, start.1).unwrap(),
+                TimeOfDay::new(end.0, end.1).unwrap(),
+                "休息".to_string(),
+            )
+            .unwrap();
+            work.subtract(&other)
+        };
+        let spans = |pieces: &[Block]| {
+            pieces
+                .iter()
+                .map(|b| format!("{}-{}", b.start_time(), b.end_time()))
+                .collect::<Vec<_>>()
+        };
+
+        // 从中间挖出，剩两段且保留名称与描述
+        let pieces = cut((10, 0), (10, 30));
+        assert_eq!(spans(&pieces), vec!["09:00-10:00", "10:30-12:00"]);
+        assert!(pieces.iter().all(|b| b.name() == "写代码" && b.description() == Some("重构")));
+        // 截掉一端，剩一段
+        assert_eq!(spans(&cut((8, 0), (9, 30))), vec!["09:30-12:00"]);
+        assert_eq!(spans(&cut((11, 0), (12, 0))), vec!["09:00-11:00"]);
+        // 完全覆盖
+        assert!(cut((8, 0), (13, 0)).is_empty());
+        // 不重叠
+        assert_eq!(cut((13, 0), (14, 0)), vec![work.clone()]);
+    }
+
+    #[test]
+    fn test_assign_lanes() {
+        let make = |start: (u16, u16), end: (u16, u16)| {
+            Block::focus(
+                TimeOfDay::new(start.0, start.1).unwrap(),
+                TimeOfDay::new(end.0, end.1).unwrap(),
+                "事项".to_string(),
+            )
+            .unwrap()
+        };
+        // 互不重叠（首尾相接也不算重叠）
+        let separate = [make((9, 0), (10, 0)), make((10, 0), (11, 0)), make((13, 0), (14, 0))];
+        assert_eq!(assign_lanes(&separate), vec![0, 0, 0]);
+
+        // 两两重叠，输入顺序打乱
+        let clash = [make((9, 30), (11, 0)), make((9, 0), (10, 0)), make((9, 45), (10, 30))];
+        assert_eq!(assign_lanes(&clash), vec![1, 0, 2]);
+
+        // 空出的列被复用，只需两列
+        let mixed = [make((9, 0), (10, 0)), make((9, 30), (11, 0)), make((10, 0), (12, 0))];
+        assert_eq!(assign_lanes(&mixed), vec![0, 1, 0]);
+        assert!(assign_lanes(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_open_block() {
+        let research = OpenBlock::new(TimeOfDay::new(14, 0).unwrap(), "研究".to_string(), false).unwrap();
+        assert_eq!(research.duration(), Duration::from_minutes(9 * 60 + 59));
+
+        let dinner = Block::meeting(TimeOfDay::new(18, 0).unwrap(), TimeOfDay::new(19, 0).unwrap(), "晚饭".to_string())
+            .unwrap();
+        let lunch = Block::meeting(TimeOfDay::new(12, 0).unwrap(), TimeOfDay::new(14, 0).unwrap(), "午饭".to_string())
+            .unwrap();
+        assert!(research.overlaps(&dinner));
+        // 首尾相接不算重叠
+        assert!(!research.overlaps(&lunch));
+
+        let closed = research.close();
+        assert_eq!(closed.end_time(), TimeOfDay::END_OF_DAY);
+        assert_eq!(closed.duration(), research.duration());
+    }
+
+    #[test]
+    fn test_open_block_invalid() {
+        assert!(matches!(
+            OpenBlock::new(TimeOfDay::new(9, 0).unwrap(), String::new(), false),
+            Err(BlockError::EmptyName)
+        ));
+        assert!(matches!(
+            OpenBlock::new(TimeOfDay::END_OF_DAY, "太晚".to_string(), false),
+            Err(BlockError::InvalidTime { .. })
+        ));
+    }
+
+    #[test]
+    fn test_content_eq() {
+        let block = |description: &str, is_fixed: bool| {
+            Block::builder()
+                .start_time(TimeOfDay::new(9, 0).unwrap())
+                .end_time(TimeOfDay::new(10, 0).unwrap())
+                .name("会议".to_string())
+                .description(Some(description.to_string()))
+                .is_fixed(is_fixed)
+                .build()
+                .unwrap()
+        };
+        // 只有是否固定不同
+        assert!(block("周会", true).content_eq(&block("周会", false)));
+        assert_ne!(block("周会", true), block("周会", false));
+        // 描述不同
+        assert!(!block("周会", false).content_eq(&block("月会", false)));
+    }
+
+    #[test]
+    fn test_apply_description_template() {
+        let mut block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 30).unwrap())
+            .name("会议 {end}".to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap();
+        block
+            .apply_description_template("{name}：{start} 到 {end}，共 {duration} 分钟 {unknown} {")
+            .unwrap();
+        assert_eq!(block.description(), Some("会议 {end}：09:00 到 10:30，共 90 分钟 {unknown} {"));
+
+        block.set_locked(true);
+        assert!(matches!(block.apply_description_template("{name}"), Err(BlockError::Locked)));
+    }
+
+    #[test]
+    fn test_set_times_unchecked() {
+        let mut block = Block::meeting(TimeOfDay::new(9, 0).unwrap(), TimeOfDay::new(10, 0).unwrap(), "例会".to_string())
+            .unwrap();
+        block.set_locked(true);
+        // 固定且锁定的时间段同样会被直接修改
+        block.set_times_unchecked(TimeOfDay::new(9, 30).unwrap(), TimeOfDay::new(11, 0).unwrap());
+        assert_eq!(block.start_time(), TimeOfDay::new(9, 30).unwrap());
+        assert_eq!(block.end_time(), TimeOfDay::new(11, 0).unwrap());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "必须晚于开始时间")]
+    fn test_set_times_unchecked_debug_assert() {
+        let mut block = Block::focus(TimeOfDay::new(9, 0).unwrap(), TimeOfDay::new(10, 0).unwrap(), "专注".to_string())
+            .unwrap();
+        block.set_times_unchecked(TimeOfDay::new(10, 0).unwrap(), TimeOfDay::new(9, 0).unwrap());
+    }
+}
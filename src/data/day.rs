@@ -0,0 +1,3220 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use log::{error, warn};
+
+use crate::data::block::{Block, BlockError};
+use crate::data::duration::Duration;
+use crate::data::template::Template;
+use crate::data::timeofday::TimeOfDay;
+use crate::utils::{self, Locale};
+
+/// 默认的撤销历史最大深度
+const DEFAULT_MAX_UNDO_DEPTH: usize = 100;
+
+/// 一天的日程：按开始时间排序且互不重叠的时间段集合
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    /// 按开始时间升序排列的时间段
+    blocks: Vec<Block>,
+    /// 可撤销的编辑记录（末尾为最近一次）
+    undo_stack: Vec<Edit>,
+    /// 已撤销、可重做的编辑记录
+    redo_stack: Vec<Edit>,
+    /// 撤销历史的最大深度
+    max_undo_depth: usize,
+    /// 时间段的最短时长（分钟），None 表示不限制
+    min_duration: Option<u16>,
+    /// 变更回调
+    observers: Observers,
+    /// 最近一次 `free_gaps` 的结果，时间段变化时清空
+    gap_cache: GapCache,
+}
+
+/// 缓存的空闲时间段：(查询窗口, 该窗口内的空闲时间段)
+///
+/// 只缓存最近一次查询的窗口；界面反复以同一窗口查询，足以命中。结果以 `Arc` 共享，命中时不复制。
+#[derive(Debug, Clone, Default)]
+struct GapCache(RefCell<Option<(TimeRange, Arc<[TimeRange]>)>>);
+
+/// 时间区间 [开始, 结束)
+type TimeRange = (TimeOfDay, TimeOfDay);
+
+/// 日程变更事件，由 `Schedule::on_change` 注册的回调接收
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleEvent {
+    /// 加入了时间段
+    Added(Block),
+    /// 移除了时间段
+    Removed(Block),
+    /// 时间段的起止时间被修改
+    Rescheduled { before: Block, after: Block },
+}
+
+/// 日程变更回调
+pub type ChangeCallback = Box<dyn FnMut(&ScheduleEvent) + Send>;
+
+/// 变更回调列表；克隆日程时不复制回调
+#[derive(Default)]
+struct Observers(Vec<ChangeCallback>);
+
+impl Clone for Observers {
+    fn clone(&self) -> Observers {
+        Observers::default()
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+/// 一次可撤销的编辑
+#[derive(Debug, Clone)]
+enum Edit {
+    /// 添加了时间段
+    Added(Block),
+    /// 删除了时间段
+    Removed(Block),
+    /// 用新时间段替换了旧时间段（包括修改起止时间）
+    Replaced { before: Block, after: Block },
+    /// 批量操作，整体替换了全部时间段
+    Bulk { before: Vec<Block>, after: Vec<Block> },
+}
+
+impl Edit {
+    /// 编辑对应的变更事件，`reverse` 为 true 时给出撤销该编辑产生的事件
+    fn events(&self, reverse: bool) -> Vec<ScheduleEvent> {
+        match (self, reverse) {
+            (Edit::Added(block), false) | (Edit::Removed(block), true) => vec![ScheduleEvent::Added(block.clone())],
+            (Edit::Removed(block), false) | (Edit::Added(block), true) => vec![ScheduleEvent::Removed(block.clone())],
+            (Edit::Replaced { before, after }, false) | (Edit::Replaced { before: after, after: before }, true) => {
+                vec![ScheduleEvent::Rescheduled {
+                    before: before.clone(),
+                    after: after.clone(),
+                }]
+            }
+            (Edit::Bulk { before, after }, false) | (Edit::Bulk { before: after, after: before }, true) => before
+                .iter()
+                .filter(|b| !after.contains(b))
+                .map(|b| ScheduleEvent::Removed(b.clone()))
+                .chain(after.iter().filter(|b| !before.contains(b)).map(|b| ScheduleEvent::Added(b.clone())))
+                .collect(),
+        }
+    }
+}
+
+impl Default for Schedule {
+    fn default() -> Schedule {
+        Schedule::new()
+    }
+}
+
+impl Schedule {
+    /// 创建空日程
+    pub fn new() -> Schedule {
+        Schedule {
+            blocks: Vec::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            max_undo_depth: DEFAULT_MAX_UNDO_DEPTH,
+            min_duration: None,
+            observers: Observers::default(),
+            gap_cache: GapCache::default(),
+        }
+    }
+    /// 设置撤销历史的最大深度，超出时丢弃最早的记录
+    pub fn with_max_undo_depth(mut self, depth: usize) -> Schedule {
+        self.max_undo_depth = depth;
+        self.trim_history();
+        self
+    }
+    /// 设置时间段的最短时长（分钟），之后添加、调整、合并进来或裁剪得更短的时间段会被拒绝
+    ///
+    /// 只约束之后的修改，已有的时间段不受影响；`try_from_blocks` 等构造出的新日程没有最短时长。
+    pub fn with_min_duration(mut self, mins: u16) -> Schedule {
+        self.min_duration = Some(mins);
+        self
+    }
+    /// 注册变更回调，之后每次添加、删除、调整时间段（包括撤销与重做）都会收到对应事件
+    ///
+    /// 批量操作按时间段的增删逐条通知。克隆出的日程不继承回调。
+    pub fn on_change(&mut self, callback: ChangeCallback) {
+        self.observers.0.push(callback);
+    }
+    /// 获取全部时间段（按开始时间排序）
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+    /// 按开始时间顺序遍历时间段
+    pub fn iter(&self) -> std::slice::Iter<'_, Block> {
+        self.blocks.iter()
+    }
+    /// 时间段数量
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+    /// 是否为空日程
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+    /// 添加时间段，与已有时间段重叠时返回错误
+    ///
+    /// 二分查找插入位置；由于已有时间段有序且互不重叠，只需检查前后两个相邻时间段。
+    pub fn add_block(&mut self, block: Block) -> Result<(), ScheduleError> {
+        self.check_min_duration(&block)?;
+        let index = self.index_of_time(block.start_time());
+        let neighbors = index.checked_sub(1).into_iter().chain(Some(index));
+        if let Some(existing) = neighbors
+            .filter_map(|i| self.blocks.get(i))
+            .find(|b| b.overlaps(&block))
+        {
+            return Err(ScheduleError::Overlap {
+                existing: Box::new(existing.clone()),
+                incoming: Box::new(block),
+            });
+        }
+        self.blocks_mut().insert(index, block.clone());
+        self.record(Edit::Added(block));
+        Ok(())
+    }
+    /// 按优先级添加时间段：与之重叠的时间段优先级都严格更低时，将它们移出日程并返回
+    ///
+    /// 任一重叠的时间段优先级相同或更高时返回错误，日程保持不变。整个操作作为一次编辑记入撤销历史。
+    pub fn add_block_priority(&mut self, block: Block) -> Result<Vec<Block>, ScheduleError> {
+        self.check_min_duration(&block)?;
+        if let Some(existing) = self
+            .blocks
+            .iter()
+            .find(|b| b.overlaps(&block) && b.priority() >= block.priority())
+        {
+            return Err(ScheduleError::Overlap {
+                existing: Box::new(existing.clone()),
+                incoming: Box::new(block),
+            });
+        }
+        let (evicted, mut kept): (Vec<Block>, Vec<Block>) =
+            self.blocks.iter().cloned().partition(|b| b.overlaps(&block));
+        let index = kept.partition_point(|b| b.start_time() <= block.start_time());
+        kept.insert(index, block);
+        self.replace_all(kept);
+        Ok(evicted)
+    }
+    /// 添加时间段，与已有时间段重叠时逐分钟向后挪动，最多挪动 `max_nudge_minutes` 分钟
+    ///
+    /// 返回实际插入的时间段（可能已被挪动）。挪动范围内找不到空位时返回最初的重叠错误，日程保持不变。
+    pub fn add_block_nudged(&mut self, block: Block, max_nudge_minutes: u16) -> Result<&Block, ScheduleError> {
+        self.check_min_duration(&block)?;
+        let mut first_conflict = None;
+        for nudge in 0..=max_nudge_minutes {
+            let offset = Duration::from_minutes(nudge);
+            let (Some(start), Some(end)) = (
+                block.start_time().checked_add(offset),
+                block.end_time().checked_add(offset),
+            ) else {
+                break;
+            };
+            let mut candidate = block.clone();
+            if nudge > 0 {
+                candidate.set_time(start, end)?;
+            }
+            match self.blocks.iter().find(|b| b.overlaps(&candidate)) {
+                Some(existing) => {
+                    first_conflict.get_or_insert_with(|| existing.clone());
+                }
+                None => {
+                    self.add_block(candidate)?;
+                    return Ok(&self.blocks[self.index_of_time(start) - 1]);
+                }
+            }
+        }
+        Err(ScheduleError::Overlap {
+            existing: Box::new(first_conflict.expect("至少尝试过原位置")),
+            incoming: Box::new(block),
+        })
+    }
+    /// 开始时间为 `time` 的时间段应插入的位置（排在开始时间相同的时间段之后）
+    pub fn index_of_time(&self, time: TimeOfDay) -> usize {
+        self.blocks.partition_point(|b| b.start_time() <= time)
+    }
+    /// 按索引删除时间段
+    pub fn remove_block(&mut self, index: usize) -> Result<Block, ScheduleError> {
+        if index >= self.blocks.len() {
+            return Err(ScheduleError::IndexOutOfBounds(index));
+        }
+        let removed = self.blocks_mut().remove(index);
+        self.record(Edit::Removed(removed.clone()));
+        Ok(removed)
+    }
+    /// 只保留满足条件的时间段，与 `Vec::retain` 相同，保持原有顺序
+    ///
+    /// 删除时间段不会产生重叠，无需重新校验。整个操作作为一次编辑记入撤销历史。
+    pub fn retain<F: FnMut(&Block) -> bool>(&mut self, mut pred: F) {
+        let kept = self.blocks.iter().filter(|b| pred(b)).cloned().collect();
+        self.replace_all(kept);
+    }
+    /// 删除所有名称完全匹配的时间段，返回删除的数量
+    pub fn remove_by_name(&mut self, name: &str) -> usize {
+        self.remove_where(|b| b.name() == name)
+    }
+    /// 删除所有名称匹配（忽略大小写）的时间段，返回删除的数量
+    pub fn remove_by_name_ignore_case(&mut self, name: &str) -> usize {
+        let name = name.to_lowercase();
+        self.remove_where(|b| b.name().to_lowercase() == name)
+    }
+    /// 删除所有满足条件的时间段，返回删除的数量；整个操作作为一次编辑记入撤销历史
+    fn remove_where<F: Fn(&Block) -> bool>(&mut self, pred: F) -> usize {
+        let before = self.blocks.len();
+        self.retain(|b| !pred(b));
+        before - self.blocks.len()
+    }
+    /// 将时间段整体平移若干分钟（负数表示提前），保持时长不变
+    pub fn shift_block(&mut self, index: usize, delta_minutes: i32) -> Result<(), ScheduleError> {
+        let block = self.blocks.get(index).ok_or(ScheduleError::IndexOutOfBounds(index))?;
+        let shift = |time: TimeOfDay| {
+            u16::try_from(i32::from(u16::from(time)) + delta_minutes)
+                .ok()
+                .and_then(|minutes| TimeOfDay::try_from(minutes).ok())
+        };
+        let (Some(start), Some(end)) = (shift(block.start_time()), shift(block.end_time())) else {
+            return Err(ScheduleError::OutOfDay);
+        };
+        self.reschedule_block(index, start, end)
+    }
+    /// 连锁平移：把第 `index` 个时间段及其后所有非固定时间段一起平移若干分钟（负数表示提前）
+    ///
+    /// 其余时间段（之前的和之后固定的）不动；平移后与它们重叠、越过它们（如跳过固定时间段）
+    /// 或超出一天时返回错误，日程保持不变。整个操作作为一次编辑记入撤销历史。
+    pub fn shift_from(&mut self, index: usize, delta_minutes: i32) -> Result<(), ScheduleError> {
+        if index >= self.blocks.len() {
+            return Err(ScheduleError::IndexOutOfBounds(index));
+        }
+        let shift = |time: TimeOfDay| {
+            u16::try_from(i32::from(u16::from(time)) + delta_minutes)
+                .ok()
+                .and_then(|minutes| TimeOfDay::try_from(minutes).ok())
+                .ok_or(ScheduleError::OutOfDay)
+        };
+        let mut moved = Vec::new();
+        let mut staying = Vec::new();
+        for (i, block) in self.blocks.iter().enumerate() {
+            if i == index || (i > index && !block.is_fixed()) {
+                let mut shifted = block.clone();
+                shifted.set_time(shift(block.start_time())?, shift(block.end_time())?)?;
+                moved.push((block, shifted));
+            } else {
+                staying.push(block.clone());
+            }
+        }
+        for (original, block) in &moved {
+            // 原位置与新位置之间扫过的范围，挡在其中的时间段同样视为冲突
+            let swept_start = original.start_time().min(block.start_time());
+            let swept_end = original.end_time().max(block.end_time());
+            if let Some(existing) = staying
+                .iter()
+                .find(|b| b.start_time() < swept_end && swept_start < b.end_time())
+            {
+                return Err(ScheduleError::Overlap {
+                    existing: Box::new(existing.clone()),
+                    incoming: Box::new(block.clone()),
+                });
+            }
+        }
+        staying.extend(moved.into_iter().map(|(_, block)| block));
+        staying.sort_by_key(|b| b.start_time());
+        self.replace_all(staying);
+        Ok(())
+    }
+    /// 修改时间段的起止时间，并与其余时间段重新校验重叠
+    ///
+    /// 固定时间段不能修改；新时间冲突时返回错误，日程保持不变。
+    pub fn reschedule_block(&mut self, index: usize, start: TimeOfDay, end: TimeOfDay) -> Result<(), ScheduleError> {
+        let mut after = self.blocks.get(index).ok_or(ScheduleError::IndexOutOfBounds(index))?.clone();
+        after.set_time(start, end)?;
+        self.replace_block(index, after).map(|_| ())
+    }
+    /// 把时间段移到从 `new_start` 开始（如拖放），时长不变，并与其余时间段重新校验重叠
+    ///
+    /// 固定时间段不能移动；移动后超出一天时返回 `OutOfDay`，冲突时返回错误，日程保持不变。
+    pub fn move_block_to(&mut self, index: usize, new_start: TimeOfDay) -> Result<(), ScheduleError> {
+        let block = self.blocks.get(index).ok_or(ScheduleError::IndexOutOfBounds(index))?;
+        let end = new_start.checked_add(block.duration()).ok_or(ScheduleError::OutOfDay)?;
+        self.reschedule_block(index, new_start, end)
+    }
+    /// 把时间段移到 `free_gaps(day_start, day_end)` 中第 `gap_index` 个空闲时间段的开头，时长不变
+    ///
+    /// 固定时间段不能移动；空闲时间段放不下时返回 `NotEnoughTime`；`gap_index` 越界时返回 `IndexOutOfBounds`。
+    pub fn move_to_gap(
+        &mut self,
+        block_index: usize,
+        gap_index: usize,
+        day_start: TimeOfDay,
+        day_end: TimeOfDay,
+    ) -> Result<(), ScheduleError> {
+        let block = self.blocks.get(block_index).ok_or(ScheduleError::IndexOutOfBounds(block_index))?;
+        if block.is_fixed() {
+            return Err(BlockError::FixedBlockTimeChange.into());
+        }
+        let duration = block.duration();
+        let (gap_start, gap_end) = *self
+            .free_gaps(day_start, day_end)
+            .get(gap_index)
+            .ok_or(ScheduleError::IndexOutOfBounds(gap_index))?;
+        let available = gap_end.duration_since(gap_start).expect("空闲时间段首尾有序");
+        if duration > available {
+            return Err(ScheduleError::NotEnoughTime {
+                needed: duration.as_minutes(),
+                available: available.as_minutes(),
+            });
+        }
+        let end = gap_start.checked_add(duration).expect("放得下即不会超出一天");
+        self.reschedule_block(block_index, gap_start, end)
+    }
+    /// 用新时间段替换指定索引的时间段，返回被替换的旧时间段
+    ///
+    /// 锁定的时间段不能替换；新时间段与其余时间段（不含被替换的那个）重叠时返回错误，日程保持不变；
+    /// 开始时间变化时重新排序。
+    pub fn replace_block(&mut self, index: usize, new: Block) -> Result<Block, ScheduleError> {
+        let old = self.blocks.get(index).ok_or(ScheduleError::IndexOutOfBounds(index))?;
+        if old.is_locked() {
+            return Err(BlockError::Locked.into());
+        }
+        self.check_min_duration(&new)?;
+        if let Some(existing) = self
+            .blocks
+            .iter()
+            .enumerate()
+            .find(|(i, b)| *i != index && b.overlaps(&new))
+            .map(|(_, b)| b)
+        {
+            return Err(ScheduleError::Overlap {
+                existing: Box::new(existing.clone()),
+                incoming: Box::new(new),
+            });
+        }
+        let before = self.blocks_mut().remove(index);
+        self.insert_sorted(new.clone());
+        self.record(Edit::Replaced {
+            before: before.clone(),
+            after: new,
+        });
+        Ok(before)
+    }
+    /// 紧凑排列：在保持顺序和时长的前提下，把所有非固定时间段从 `day_start` 起尽量提前
+    ///
+    /// 固定时间段位置不变，作为不可移动的锚点；排不下时返回错误且日程保持不变。
+    pub fn compact(&mut self, day_start: TimeOfDay) -> Result<(), ScheduleError> {
+        let fixed: Vec<&Block> = self.blocks.iter().filter(|b| b.is_fixed()).collect();
+        let mut compacted: Vec<Block> = fixed.iter().map(|b| (*b).clone()).collect();
+        let mut cursor = u16::from(day_start);
+        for block in self.blocks.iter().filter(|b| !b.is_fixed()) {
+            let duration = block.duration().minutes();
+            // 跳过挡路的固定时间段，直到找到放得下的位置
+            while let Some(anchor) = fixed
+                .iter()
+                .find(|f| u16::from(f.start_time()) < cursor + duration && cursor < u16::from(f.end_time()))
+            {
+                cursor = u16::from(anchor.end_time());
+            }
+            let (Ok(start), Ok(end)) = (TimeOfDay::try_from(cursor), TimeOfDay::try_from(cursor + duration)) else {
+                return Err(ScheduleError::OutOfDay);
+            };
+            let mut moved = block.clone();
+            moved.set_time(start, end)?;
+            compacted.push(moved);
+            cursor += duration;
+        }
+        compacted.sort_by_key(|b| b.start_time());
+        self.replace_all(compacted);
+        Ok(())
+    }
+    /// 均匀分布空闲时间：在窗口 [day_start, day_end) 内重新安排非固定时间段，使间隔尽量相等
+    ///
+    /// 固定时间段不动，把窗口分成若干段；每段内的非固定时间段保持顺序和时长，
+    /// 段首、段尾和彼此之间的空闲时间均分（除不尽的分钟给靠前的间隔）。
+    /// 某段放不下时返回错误且日程保持不变。
+    pub fn rebalance(&mut self, day_start: TimeOfDay, day_end: TimeOfDay) -> Result<(), ScheduleError> {
+        let mut rebalanced = Vec::with_capacity(self.blocks.len());
+        let mut segment: Vec<&Block> = Vec::new();
+        let mut segment_start = u16::from(day_start);
+        for block in &self.blocks {
+            if block.is_fixed() {
+                let segment_end = u16::from(block.start_time()).min(u16::from(day_end));
+                rebalanced.extend(spread_evenly(&segment, segment_start, segment_end)?);
+                segment.clear();
+                rebalanced.push(block.clone());
+                segment_start = segment_start.max(u16::from(block.end_time()));
+            } else {
+                segment.push(block);
+            }
+        }
+        rebalanced.extend(spread_evenly(&segment, segment_start, u16::from(day_end))?);
+        rebalanced.sort_by_key(|b| b.start_time());
+        self.replace_all(rebalanced);
+        Ok(())
+    }
+    /// 合并另一个日程，发生重叠时按 `strategy` 处理
+    ///
+    /// 使用 `MergeStrategy::Error` 时遇到第一个冲突即返回错误，日程保持不变；
+    /// 要并入的时间段短于最短时长时同样返回错误。
+    pub fn merge(&mut self, other: Schedule, strategy: MergeStrategy) -> Result<(), ScheduleError> {
+        let mut merged = self.blocks.clone();
+        for incoming in other {
+            let conflicts: Vec<usize> = merged
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.overlaps(&incoming))
+                .map(|(i, _)| i)
+                .collect();
+            if !conflicts.is_empty() {
+                match strategy {
+                    MergeStrategy::KeepExisting => continue,
+                    MergeStrategy::PreferIncoming => {
+                        for index in conflicts.into_iter().rev() {
+                            merged.remove(index);
+                        }
+                    }
+                    MergeStrategy::Error => {
+                        return Err(ScheduleError::Overlap {
+                            existing: Box::new(merged[conflicts[0]].clone()),
+                            incoming: Box::new(incoming),
+                        });
+                    }
+                }
+            }
+            self.check_min_duration(&incoming)?;
+            let index = merged.partition_point(|b| b.start_time() <= incoming.start_time());
+            merged.insert(index, incoming);
+        }
+        self.replace_all(merged);
+        Ok(())
+    }
+    /// 整体替换全部时间段并记入撤销历史（调用方保证有序且不重叠）
+    fn replace_all(&mut self, blocks: Vec<Block>) {
+        let before = std::mem::replace(self.blocks_mut(), blocks);
+        if before != self.blocks {
+            self.record(Edit::Bulk { before, after: self.blocks.clone() });
+        }
+    }
+    /// 按开始时间顺序消除重叠（用于宽松导入的数据）：后开始的时间段被裁掉与前一个重叠的部分，裁完为空的直接丢弃
+    ///
+    /// 只移动开始时间，其他字段不变；执行后日程有序且互不重叠。需要裁剪或丢弃的时间段是固定的或锁定的、
+    /// 或者裁剪后短于最短时长时返回错误，日程保持不变。整个操作作为一次编辑记入撤销历史。
+    pub fn resolve_overlaps_by_clipping(&mut self) -> Result<(), ScheduleError> {
+        let mut sorted = self.blocks.clone();
+        sorted.sort_by_key(|b| b.start_time());
+        let mut resolved: Vec<Block> = Vec::with_capacity(sorted.len());
+        for mut block in sorted {
+            if let Some(last) = resolved.last()
+                && block.start_time() < last.end_time()
+            {
+                if block.is_locked() {
+                    return Err(BlockError::Locked.into());
+                }
+                if block.is_fixed() {
+                    return Err(BlockError::FixedBlockTimeChange.into());
+                }
+                if block.end_time() <= last.end_time() {
+                    warn!("时间段 {} 被 {} 完全覆盖，已丢弃", block.name(), last.name());
+                    continue;
+                }
+                block.set_times_unchecked(last.end_time(), block.end_time());
+                self.check_min_duration(&block)?;
+            }
+            resolved.push(block);
+        }
+        self.replace_all(resolved);
+        Ok(())
+    }
+    /// 按第一个标签为时间段着色，同一标签总是得到调色板中的同一颜色
+    ///
+    /// 标签经稳定的哈希映射到调色板下标，结果与时间段顺序无关。没有标签的时间段保留原颜色；
+    /// 调色板为空时不做修改。整个操作作为一次编辑记入撤销历史。
+    pub fn assign_colors_by_tag(&mut self, palette: &[&str]) {
+        if palette.is_empty() {
+            return;
+        }
+        let mut blocks = self.blocks.clone();
+        for block in &mut blocks {
+            if let Some(tag) = block.tags().first() {
+                let color = palette[(utils::stable_hash(tag) % palette.len() as u64) as usize];
+                block.set_color(Some(color.to_string()));
+            }
+        }
+        self.replace_all(blocks);
+    }
+    /// 一次性整理日程：按开始时间排序，合并首尾相接且名称相同的时间段，仍有重叠时返回错误
+    ///
+    /// 合并后的时间段沿用前一个时间段的其他字段。出错时日程保持不变；重复调用结果不变。
+    pub fn normalize(&mut self) -> Result<(), ScheduleError> {
+        let sorted = Schedule::try_from_blocks(self.blocks.clone())?;
+        let mut normalized: Vec<Block> = Vec::with_capacity(sorted.len());
+        for block in sorted {
+            match normalized.last_mut() {
+                Some(last) if last.end_time() == block.start_time() && last.name() == block.name() => {
+                    last.set_times_unchecked(last.start_time(), block.end_time());
+                }
+                _ => normalized.push(block),
+            }
+        }
+        self.replace_all(normalized);
+        Ok(())
+    }
+    /// 比较两个日程，得到从 `self` 到 `other` 的新增、删除和修改
+    ///
+    /// 以 `Block::same_slot` 匹配两边的时间段；匹配但其余字段不同的视为修改。
+    pub fn diff(&self, other: &Schedule) -> ScheduleDiff {
+        let mut diff = ScheduleDiff::default();
+        for old in &self.blocks {
+            match other.blocks.iter().find(|new| old.same_slot(new)) {
+                None => diff.removed.push(old.clone()),
+                Some(new) if new != old => diff.modified.push((old.clone(), new.clone())),
+                Some(_) => {}
+            }
+        }
+        for new in &other.blocks {
+            if !self.blocks.iter().any(|old| old.same_slot(new)) {
+                diff.added.push(new.clone());
+            }
+        }
+        diff
+    }
+    /// 按外部标识查找时间段；标识不要求唯一，有多个时返回时间最早的一个
+    pub fn find_by_id(&self, id: &str) -> Option<&Block> {
+        self.blocks.iter().find(|b| b.id() == Some(id))
+    }
+    /// 按名称搜索时间段（不区分大小写），返回 (索引, 相关度) 并按相关度从高到低排列，相关度相同时按时间顺序
+    ///
+    /// 相关度：完全相同 1.0 > 前缀 0.75 > 包含 0.5 > 模糊匹配（按顺序包含查询的全部字符）不超过 0.25，
+    /// 模糊匹配中名称越短越靠前。不匹配的时间段不返回；空查询返回空列表。
+    pub fn search(&self, query: &str) -> Vec<(usize, f32)> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut results: Vec<(usize, f32)> = self
+            .blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, block)| search_score(&block.name().to_lowercase(), &query).map(|score| (index, score)))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1));
+        results
+    }
+    /// 找出重复录入的时间段：返回互为 `same_slot` 的索引分组，只有一个元素的组不返回
+    ///
+    /// 通过 `add_block` 维护的日程不会出现重复，此方法用于检查导入的数据。
+    pub fn find_duplicates(&self) -> Vec<Vec<usize>> {
+        let mut grouped = vec![false; self.blocks.len()];
+        let mut groups = Vec::new();
+        for i in 0..self.blocks.len() {
+            if grouped[i] {
+                continue;
+            }
+            let group: Vec<usize> = (i..self.blocks.len())
+                .filter(|&j| self.blocks[i].same_slot(&self.blocks[j]))
+                .collect();
+            if group.len() > 1 {
+                for &j in &group {
+                    grouped[j] = true;
+                }
+                groups.push(group);
+            }
+        }
+        groups
+    }
+    /// 以事务方式执行多步编辑：闭包返回错误时恢复到执行前的状态（包括撤销历史）并返回该错误
+    ///
+    /// 成功时闭包内的全部编辑合并为一次编辑记入撤销历史。变更回调会收到闭包内每一步的事件，
+    /// 回滚时再收到恢复原状的事件。
+    pub fn transaction<F: FnOnce(&mut Schedule) -> Result<(), ScheduleError>>(&mut self, f: F) -> Result<(), ScheduleError> {
+        let before = self.blocks.clone();
+        let undo_stack = self.undo_stack.clone();
+        let redo_stack = self.redo_stack.clone();
+        let result = f(self);
+        let after = std::mem::replace(self.blocks_mut(), before.clone());
+        self.undo_stack = undo_stack;
+        self.redo_stack = redo_stack;
+        if before == after {
+            return result;
+        }
+        let edit = Edit::Bulk { before, after };
+        match result {
+            Ok(()) => {
+                self.replay(&edit);
+                self.undo_stack.push(edit);
+                self.redo_stack.clear();
+                self.trim_history();
+                Ok(())
+            }
+            Err(e) => {
+                self.notify(&edit, true);
+                Err(e)
+            }
+        }
+    }
+    /// 撤销最近一次编辑，没有可撤销的编辑时返回 false
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.undo_stack.pop() else {
+            return false;
+        };
+        self.revert(&edit);
+        self.notify(&edit, true);
+        self.redo_stack.push(edit);
+        true
+    }
+    /// 重做最近一次撤销的编辑，没有可重做的编辑时返回 false
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.redo_stack.pop() else {
+            return false;
+        };
+        self.replay(&edit);
+        self.notify(&edit, false);
+        self.undo_stack.push(edit);
+        true
+    }
+    /// 是否有可撤销的编辑
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+    /// 是否有可重做的编辑
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+    /// 清空撤销/重做历史
+    pub fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+    /// 用给定时间段构造新日程，沿用当前配置但不带编辑历史（调用方保证有序且不重叠）
+    fn derive_with(&self, blocks: Vec<Block>) -> Schedule {
+        Schedule {
+            blocks,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            max_undo_depth: self.max_undo_depth,
+            min_duration: self.min_duration,
+            observers: Observers::default(),
+            gap_cache: GapCache::default(),
+        }
+    }
+    /// 可修改的时间段列表，同时清空空闲时间段的缓存；所有对 `blocks` 的修改都应经过这里
+    fn blocks_mut(&mut self) -> &mut Vec<Block> {
+        self.gap_cache.0.get_mut().take();
+        &mut self.blocks
+    }
+    /// 检查时间段是否满足最短时长
+    fn check_min_duration(&self, block: &Block) -> Result<(), ScheduleError> {
+        match self.min_duration {
+            Some(min) if block.duration().minutes() < min => Err(ScheduleError::TooShort {
+                min,
+                actual: block.duration().as_minutes(),
+            }),
+            _ => Ok(()),
+        }
+    }
+    /// 按开始时间插入到正确位置（调用方保证不重叠）
+    fn insert_sorted(&mut self, block: Block) {
+        let index = self.index_of_time(block.start_time());
+        self.blocks_mut().insert(index, block);
+    }
+    /// 删除与给定时间段完全相同的时间段
+    fn remove_exact(&mut self, block: &Block) {
+        if let Some(index) = self.blocks.iter().position(|b| b == block) {
+            self.blocks_mut().remove(index);
+        }
+    }
+    /// 记录一次新的编辑，并清空重做历史
+    fn record(&mut self, edit: Edit) {
+        self.notify(&edit, false);
+        self.undo_stack.push(edit);
+        self.redo_stack.clear();
+        self.trim_history();
+    }
+    /// 把编辑对应的事件通知给所有回调
+    fn notify(&mut self, edit: &Edit, reverse: bool) {
+        if self.observers.0.is_empty() {
+            return;
+        }
+        for event in edit.events(reverse) {
+            for observer in &mut self.observers.0 {
+                observer(&event);
+            }
+        }
+    }
+    /// 丢弃超出最大深度的最早记录
+    fn trim_history(&mut self) {
+        if self.undo_stack.len() > self.max_undo_depth {
+            let excess = self.undo_stack.len() - self.max_undo_depth;
+            self.undo_stack.drain(..excess);
+        }
+    }
+    /// 执行编辑的逆操作
+    fn revert(&mut self, edit: &Edit) {
+        match edit {
+            Edit::Added(block) => self.remove_exact(block),
+            Edit::Removed(block) => self.insert_sorted(block.clone()),
+            Edit::Replaced { before, after } => {
+                self.remove_exact(after);
+                self.insert_sorted(before.clone());
+            }
+            Edit::Bulk { before, .. } => *self.blocks_mut() = before.clone(),
+        }
+    }
+    /// 重新执行编辑
+    fn replay(&mut self, edit: &Edit) {
+        match edit {
+            Edit::Added(block) => self.insert_sorted(block.clone()),
+            Edit::Removed(block) => self.remove_exact(block),
+            Edit::Replaced { before, after } => {
+                self.remove_exact(before);
+                self.insert_sorted(after.clone());
+            }
+            Edit::Bulk { after, .. } => *self.blocks_mut() = after.clone(),
+        }
+    }
+    /// 由模板构建新的日程
+    pub fn from_template(template: &Template) -> Result<Schedule, ScheduleError> {
+        let mut schedule = Schedule::new();
+        for block in template.blocks() {
+            schedule.add_block(block.clone())?;
+        }
+        schedule.clear_history();
+        Ok(schedule)
+    }
+    /// 由任意顺序的时间段构建日程：排序后检查相邻时间段，重叠时返回冲突的一对
+    pub fn try_from_blocks(mut blocks: Vec<Block>) -> Result<Schedule, ScheduleError> {
+        blocks.sort_by_key(|b| b.start_time());
+        if let Some(pair) = blocks.windows(2).find(|pair| pair[0].overlaps(&pair[1])) {
+            return Err(ScheduleError::Overlap {
+                existing: Box::new(pair[0].clone()),
+                incoming: Box::new(pair[1].clone()),
+            });
+        }
+        Ok(Schedule { blocks, ..Schedule::new() })
+    }
+    /// 由 (开始, 结束, 名称) 批量构建非固定时间段的日程
+    ///
+    /// 遇到第一个不合法或与前面重叠的条目即停止，错误中包含该条目的索引（从 0 开始）。
+    pub fn from_specs(specs: &[(TimeOfDay, TimeOfDay, &str)]) -> Result<Schedule, ScheduleError> {
+        let mut schedule = Schedule::new();
+        for (index, &(start, end, name)) in specs.iter().enumerate() {
+            let wrap = |e: ScheduleError| ScheduleError::Spec { index, source: Box::new(e) };
+            let block = Block::builder()
+                .start_time(start)
+                .end_time(end)
+                .name(name.to_string())
+                .is_fixed(false)
+                .build()
+                .map_err(|e| wrap(e.into()))?;
+            schedule.add_block(block).map_err(wrap)?;
+        }
+        schedule.clear_history();
+        Ok(schedule)
+    }
+    /// 将模板应用到当前日程，返回实际加入的时间段数量
+    ///
+    /// `skip_conflicts` 为 false 时遇到第一个冲突即返回错误，日程保持不变；
+    /// 为 true 时跳过与已有时间段冲突的模板时间段。
+    pub fn apply_template(&mut self, template: &Template, skip_conflicts: bool) -> Result<usize, ScheduleError> {
+        // 先在副本上试加，全部通过后再加入自身，保证失败时日程不变
+        let mut trial = self.clone();
+        let mut accepted = Vec::new();
+        for block in template.blocks() {
+            match trial.add_block(block.clone()) {
+                Ok(()) => accepted.push(block.clone()),
+                Err(ScheduleError::Overlap { incoming, .. }) if skip_conflicts => {
+                    warn!("模板 {} 中的时间段 {} 与已有日程冲突，已跳过", template.name(), incoming.name());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        let applied = accepted.len();
+        for block in accepted {
+            self.add_block(block)?;
+        }
+        Ok(applied)
+    }
+    /// 获取窗口 [day_start, day_end) 内的所有空闲时间段（按时间顺序）
+    ///
+    /// 结果按窗口缓存，直到时间段发生变化；同一窗口的重复查询不再遍历全部时间段，
+    /// 返回的是同一份共享的结果，不复制。需要修改时用 `to_vec` 取得副本。
+    pub fn free_gaps(&self, day_start: TimeOfDay, day_end: TimeOfDay) -> Arc<[(TimeOfDay, TimeOfDay)]> {
+        let window = (day_start, day_end);
+        if let Some((cached, gaps)) = &*self.gap_cache.0.borrow()
+            && *cached == window
+        {
+            return Arc::clone(gaps);
+        }
+        let gaps: Arc<[_]> = self.iter_gaps(day_start, day_end).collect();
+        *self.gap_cache.0.borrow_mut() = Some((window, Arc::clone(&gaps)));
+        gaps
+    }
+    /// 按时间顺序惰性地逐个给出窗口 [day_start, day_end) 内的空闲时间段，找到所需的即可停止
+    pub fn iter_gaps(&self, day_start: TimeOfDay, day_end: TimeOfDay) -> impl Iterator<Item = (TimeOfDay, TimeOfDay)> + '_ {
+        let mut blocks = self.blocks.iter();
+        let mut cursor = day_start;
+        let mut finished = false;
+        std::iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+            for block in blocks.by_ref() {
+                if block.start_time() >= day_end {
+                    break;
+                }
+                if block.end_time() <= cursor {
+                    continue;
+                }
+                let gap = (block.start_time() > cursor).then_some((cursor, block.start_time()));
+                cursor = block.end_time();
+                if gap.is_some() {
+                    return gap;
+                }
+            }
+            finished = true;
+            (cursor < day_end).then_some((cursor, day_end))
+        })
+    }
+    /// 在 [day_start, deadline) 内找最晚的、至少 `needed` 分钟的空闲时间段（如“15:00 的会议前需要 30 分钟”）
+    ///
+    /// 返回整个空闲时间段（结束时间不晚于 `deadline`），没有时返回 None。
+    pub fn free_before(&self, deadline: TimeOfDay, needed: u16, day_start: TimeOfDay) -> Option<(TimeOfDay, TimeOfDay)> {
+        self.free_gaps(day_start, deadline)
+            .iter()
+            .rev()
+            .find(|(start, end)| u16::from(*end) - u16::from(*start) >= needed)
+            .copied()
+    }
+    /// 为一组 (名称, 时长分钟) 的任务建议安排时间，不修改日程
+    ///
+    /// 按顺序把每个任务放进窗口内最早能容纳它的空闲时间段，已建议的位置会占用空闲时间；
+    /// 放不下（或时长为 0）的任务对应 None。
+    pub fn suggest_placements(
+        &self,
+        tasks: &[(String, u16)],
+        day_start: TimeOfDay,
+        day_end: TimeOfDay,
+    ) -> Vec<Option<(TimeOfDay, TimeOfDay)>> {
+        let mut gaps = self.free_gaps(day_start, day_end).to_vec();
+        tasks
+            .iter()
+            .map(|&(_, minutes)| {
+                if minutes == 0 {
+                    return None;
+                }
+                let gap = gaps
+                    .iter_mut()
+                    .find(|(start, end)| u16::from(*end) - u16::from(*start) >= minutes)?;
+                let start = gap.0;
+                let end = TimeOfDay::try_from(u16::from(start) + minutes).ok()?;
+                gap.0 = end;
+                Some((start, end))
+            })
+            .collect()
+    }
+    /// 合并首尾相接的时间段（不论名称），得到最长的连续忙碌区间（按时间顺序）
+    pub fn busy_intervals(&self) -> Vec<(TimeOfDay, TimeOfDay)> {
+        let mut intervals: Vec<(TimeOfDay, TimeOfDay)> = Vec::new();
+        for block in &self.blocks {
+            match intervals.last_mut() {
+                Some((_, end)) if *end == block.start_time() => *end = block.end_time(),
+                _ => intervals.push((block.start_time(), block.end_time())),
+            }
+        }
+        intervals
+    }
+    /// 获取窗口内最长的空闲时间段，长度相同时取较早的；窗口被占满时返回 None
+    pub fn largest_gap(&self, day_start: TimeOfDay, day_end: TimeOfDay) -> Option<(TimeOfDay, TimeOfDay)> {
+        let mut largest: Option<(TimeOfDay, TimeOfDay)> = None;
+        for &(start, end) in self.free_gaps(day_start, day_end).iter() {
+            let length = u16::from(end) - u16::from(start);
+            match largest {
+                Some((s, e)) if u16::from(e) - u16::from(s) >= length => {}
+                _ => largest = Some((start, end)),
+            }
+        }
+        largest
+    }
+    /// 以滑动窗口统计全天的占用比例，返回 (窗口开始时间, 占用比例)
+    ///
+    /// 末尾不足一个窗口的部分按实际长度计算；参数为 0 时记录错误并返回空列表。
+    pub fn density(&self, window_minutes: u16, step_minutes: u16) -> Vec<(TimeOfDay, f32)> {
+        if window_minutes == 0 || step_minutes == 0 {
+            error!("窗口长度和步长必须大于 0");
+            return Vec::new();
+        }
+        const DAY_MINUTES: u16 = 24 * 60;
+        let mut result = Vec::new();
+        let mut start = 0u16;
+        while start < DAY_MINUTES {
+            let end = start.saturating_add(window_minutes).min(DAY_MINUTES);
+            let booked: u16 = self
+                .blocks
+                .iter()
+                .map(|b| {
+                    let overlap_start = u16::from(b.start_time()).max(start);
+                    let overlap_end = u16::from(b.end_time()).min(end);
+                    overlap_end.saturating_sub(overlap_start)
+                })
+                .sum();
+            let time = TimeOfDay::try_from(start).expect("窗口开始时间在一天之内");
+            result.push((time, f32::from(booked) / f32::from(end - start)));
+            start = start.saturating_add(step_minutes);
+        }
+        result
+    }
+    /// 把窗口 [day_start, day_end) 按 `slot_minutes` 切成时间槽，返回每个槽是否忙碌，用于日历的忙闲查询
+    ///
+    /// 被时间段覆盖超过一半的槽视为忙碌（恰好一半算空闲）。窗口不能整除时，末尾不足一个槽的部分
+    /// 单独成槽并按实际长度判断；槽长为 0 时记录错误并返回空列表。
+    pub fn free_busy(&self, slot_minutes: u16, day_start: TimeOfDay, day_end: TimeOfDay) -> Vec<bool> {
+        if slot_minutes == 0 {
+            error!("时间槽长度必须大于 0");
+            return Vec::new();
+        }
+        let window_end = u16::from(day_end);
+        let mut slots = Vec::new();
+        let mut start = u16::from(day_start);
+        while start < window_end {
+            let end = start.saturating_add(slot_minutes).min(window_end);
+            let booked: u16 = self
+                .blocks
+                .iter()
+                .map(|b| u16::from(b.end_time()).min(end).saturating_sub(u16::from(b.start_time()).max(start)))
+                .sum();
+            slots.push(booked * 2 > end - start);
+            start = end;
+        }
+        slots
+    }
+    /// 从 `day_start` 起每隔 `step_minutes` 取一个时刻（早于 `day_end`），给出该时刻所在时间段的名称，
+    /// 空闲时为 None，用于导出到时间记录工具
+    ///
+    /// 步长为 0 时记录错误并返回空列表。
+    pub fn activity_at_each(&self, step_minutes: u16, day_start: TimeOfDay, day_end: TimeOfDay) -> Vec<(TimeOfDay, Option<String>)> {
+        if step_minutes == 0 {
+            error!("步长必须大于 0");
+            return Vec::new();
+        }
+        let mut result = Vec::new();
+        let mut minutes = u16::from(day_start);
+        while minutes < u16::from(day_end) {
+            let time = TimeOfDay::try_from(minutes).expect("早于窗口结束时间，在一天之内");
+            let active = self
+                .index_of_time(time)
+                .checked_sub(1)
+                .map(|i| &self.blocks[i])
+                .filter(|b| b.contains(time))
+                .map(|b| b.name().to_string());
+            result.push((time, active));
+            minutes = minutes.saturating_add(step_minutes);
+        }
+        result
+    }
+    /// 筛选出满足条件的时间段组成新日程，保持原有顺序
+    pub fn filter<F: Fn(&Block) -> bool>(&self, pred: F) -> Schedule {
+        // 原日程互不重叠，子集同样不重叠，无需重新校验
+        self.derive_with(self.blocks.iter().filter(|b| pred(b)).cloned().collect())
+    }
+    /// 将时间吸附到 `threshold_minutes` 以内最近的时间段边界（开始或结束），没有则原样返回
+    ///
+    /// 距离相同时取较早的边界。
+    pub fn snap_time(&self, time: TimeOfDay, threshold_minutes: u16) -> TimeOfDay {
+        let minutes = u16::from(time);
+        self.blocks
+            .iter()
+            .flat_map(|b| [b.start_time(), b.end_time()])
+            .map(|edge| (u16::from(edge).abs_diff(minutes), edge))
+            .filter(|(distance, _)| *distance <= threshold_minutes)
+            .min()
+            .map_or(time, |(_, edge)| edge)
+    }
+    /// 生成按分钟的占用快照，用于大量重叠查询；日程修改后需重新生成
+    pub fn occupancy(&self) -> Occupancy {
+        let mut minutes = [false; 24 * 60];
+        for block in &self.blocks {
+            let range = usize::from(u16::from(block.start_time()))..usize::from(u16::from(block.end_time()));
+            minutes[range].fill(true);
+        }
+        Occupancy(minutes)
+    }
+    /// 把时间段分为 (固定的, 非固定的) 两组，各自保持时间顺序
+    pub fn partition_fixed(&self) -> (Vec<&Block>, Vec<&Block>) {
+        self.blocks.iter().partition(|b| b.is_fixed())
+    }
+    /// 固定时间段占全部时间段的比例（按数量，0.0 到 1.0），空日程返回 0.0
+    pub fn percent_fixed(&self) -> f32 {
+        if self.blocks.is_empty() {
+            return 0.0;
+        }
+        let fixed = self.blocks.iter().filter(|b| b.is_fixed()).count();
+        fixed as f32 / self.blocks.len() as f32
+    }
+    /// 一次性计算窗口 [day_start, day_end) 内的统计信息，供仪表盘使用
+    pub fn stats(&self, day_start: TimeOfDay, day_end: TimeOfDay) -> ScheduleStats {
+        let visible = self.clip_to_window(day_start, day_end);
+        let busy_minutes = visible.iter().map(Block::duration).sum::<Duration>().as_minutes();
+        let window = u32::from(u16::from(day_end).saturating_sub(u16::from(day_start)));
+        ScheduleStats {
+            busy_minutes,
+            free_minutes: window - busy_minutes,
+            block_count: visible.len(),
+            fixed_count: visible.iter().filter(|b| b.is_fixed()).count(),
+            largest_gap: self.largest_gap(day_start, day_end),
+            completion_rate: self.busy_fraction(day_start, day_end),
+        }
+    }
+    /// 窗口 [day_start, day_end) 内被占用的时间比例，窗口为空时返回 0.0
+    pub fn busy_fraction(&self, day_start: TimeOfDay, day_end: TimeOfDay) -> f32 {
+        if day_start >= day_end {
+            return 0.0;
+        }
+        let window = u16::from(day_end) - u16::from(day_start);
+        let busy: u16 = self
+            .blocks
+            .iter()
+            .filter_map(|b| b.trim_to(day_start, day_end))
+            .map(|b| b.duration().minutes())
+            .sum();
+        f32::from(busy) / f32::from(window)
+    }
+    /// 窗口内的占用比例是否超过 `max_busy_fraction`
+    ///
+    /// `max_busy_fraction` 不在 [0, 1] 范围内（包括 NaN）时返回 `InvalidFraction`。
+    pub fn is_overbooked(&self, day_start: TimeOfDay, day_end: TimeOfDay, max_busy_fraction: f32) -> Result<bool, ScheduleError> {
+        if !(0.0..=1.0).contains(&max_busy_fraction) {
+            return Err(ScheduleError::InvalidFraction(max_busy_fraction));
+        }
+        Ok(self.busy_fraction(day_start, day_end) > max_busy_fraction)
+    }
+    /// 按分界时间把一天切分为若干段（如上午/下午/晚上），跨越分界的时间段会被裁剪到各段中
+    ///
+    /// n 个分界时间得到 n + 1 段；分界时间必须严格递增。
+    pub fn split_day(&self, boundaries: &[TimeOfDay]) -> Result<Vec<Schedule>, ScheduleError> {
+        if boundaries.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(ScheduleError::InvalidBoundaries);
+        }
+        let starts = std::iter::once(TimeOfDay::MIDNIGHT).chain(boundaries.iter().copied());
+        let ends = boundaries.iter().copied().chain(std::iter::once(TimeOfDay::END_OF_DAY));
+        Ok(starts
+            .zip(ends)
+            .map(|(start, end)| self.derive_with(self.blocks.iter().filter_map(|b| b.trim_to(start, end)).collect()))
+            .collect())
+    }
+    /// 最早的开始时间（即第一个时间段的开始），空日程返回 None，用于自动调整时间轴的显示范围
+    pub fn earliest_start(&self) -> Option<TimeOfDay> {
+        self.blocks.first().map(Block::start_time)
+    }
+    /// 最晚的结束时间，空日程返回 None
+    ///
+    /// 时间段按开始时间排序而非结束时间，这里逐个比较而不依赖最后一个时间段。
+    pub fn latest_end(&self) -> Option<TimeOfDay> {
+        self.blocks.iter().map(Block::end_time).max()
+    }
+    /// 检查非固定时间段与固定时间段之间是否都留有至少 `minutes` 分钟的缓冲（如会议前后的准备和路上时间）
+    ///
+    /// 按时间顺序报告第一处违规；固定时间段之间不检查。
+    pub fn enforce_buffer(&self, minutes: u16) -> Result<(), ScheduleError> {
+        let fixed: Vec<&Block> = self.blocks.iter().filter(|b| b.is_fixed()).collect();
+        for block in self.blocks.iter().filter(|b| !b.is_fixed()) {
+            for meeting in &fixed {
+                let gap = if block.end_time() <= meeting.start_time() {
+                    u16::from(meeting.start_time()) - u16::from(block.end_time())
+                } else {
+                    u16::from(block.start_time()) - u16::from(meeting.end_time())
+                };
+                if gap < minutes {
+                    return Err(ScheduleError::BufferTooSmall {
+                        fixed: Box::new((*meeting).clone()),
+                        block: Box::new(block.clone()),
+                        gap,
+                        required: minutes,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+    /// 结束时间最早的时间段（用于区间调度等贪心算法）
+    ///
+    /// 结束时间相同时取开始较早的；日程内的时间段互不重叠，实际上不会出现并列。
+    pub fn earliest_ending(&self) -> Option<&Block> {
+        self.blocks.iter().min_by_key(|b| (b.end_time(), b.start_time()))
+    }
+    /// 与窗口 [start, end) 相交的时间段数量（判定与 `Block::overlaps` 相同，首尾相接不算），用于分页和密度检查
+    pub fn count_in_window(&self, start: TimeOfDay, end: TimeOfDay) -> usize {
+        self.blocks
+            .iter()
+            .filter(|b| b.start_time() < end && start < b.end_time())
+            .count()
+    }
+    /// 返回与窗口 [start, end) 相交的时间段，按窗口裁剪后的副本（按时间顺序），用于只绘制可见部分
+    pub fn clip_to_window(&self, start: TimeOfDay, end: TimeOfDay) -> Vec<Block> {
+        self.blocks.iter().filter_map(|b| b.trim_to(start, end)).collect()
+    }
+    /// 计算 `now` 所在时间段的进度，返回 (索引, 已过比例)，用于时间轴上的进度条
+    ///
+    /// 比例在开始时为 0.0，接近结束时趋近 1.0；时间段互不重叠，结果最多一项，`now` 在空闲时段时为空。
+    pub fn progress_at(&self, now: TimeOfDay) -> Vec<(usize, f32)> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.contains(now))
+            .map(|(index, b)| {
+                let elapsed = u16::from(now) - u16::from(b.start_time());
+                (index, f32::from(elapsed) / f32::from(b.duration().minutes()))
+            })
+            .collect()
+    }
+    /// 找出安排时间最多的小时，返回 (小时, 占用分钟数)
+    ///
+    /// 跨越整点的时间段按分钟拆分到各自的小时；并列时取较早的小时。
+    pub fn busiest_hour(&self) -> Option<(u16, u32)> {
+        if self.blocks.is_empty() {
+            return None;
+        }
+        let mut minutes_per_hour = [0u32; 24];
+        for block in &self.blocks {
+            for minute in u16::from(block.start_time())..u16::from(block.end_time()) {
+                minutes_per_hour[usize::from(minute / 60)] += 1;
+            }
+        }
+        let mut busiest = (0u16, minutes_per_hour[0]);
+        for (hour, &minutes) in minutes_per_hour.iter().enumerate().skip(1) {
+            if minutes > busiest.1 {
+                busiest = (hour as u16, minutes);
+            }
+        }
+        Some(busiest)
+    }
+    /// 按开始时间所在的小时分组，用于按小时渲染的日程视图
+    ///
+    /// 每个时间段只出现在开始的那个小时下；没有时间段开始的小时不出现在结果中。
+    pub fn group_by_hour(&self) -> BTreeMap<u16, Vec<&Block>> {
+        let mut groups: BTreeMap<u16, Vec<&Block>> = BTreeMap::new();
+        for block in &self.blocks {
+            groups.entry(block.start_time().hour()).or_default().push(block);
+        }
+        groups
+    }
+}
+
+/// 合并日程时处理重叠的策略
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// 保留已有时间段，跳过冲突的新时间段
+    KeepExisting,
+    /// 以新时间段为准，删除与之冲突的已有时间段
+    PreferIncoming,
+    /// 遇到冲突即返回错误
+    Error,
+}
+
+/// 名称与查询（均已转为小写）的相关度，不匹配时返回 None
+fn search_score(name: &str, query: &str) -> Option<f32> {
+    if name == query {
+        return Some(1.0);
+    }
+    if name.starts_with(query) {
+        return Some(0.75);
+    }
+    if name.contains(query) {
+        return Some(0.5);
+    }
+    let mut chars = name.chars();
+    let is_subsequence = query.chars().all(|q| chars.any(|c| c == q));
+    is_subsequence.then(|| 0.25 * query.chars().count() as f32 / name.chars().count() as f32)
+}
+
+/// 把时间段按顺序放进 [start, end)，首尾和彼此之间的空闲时间均分
+fn spread_evenly(blocks: &[&Block], start: u16, end: u16) -> Result<Vec<Block>, ScheduleError> {
+    if blocks.is_empty() {
+        return Ok(Vec::new());
+    }
+    let available = u32::from(end.saturating_sub(start));
+    let needed = blocks.iter().map(|b| b.duration()).sum::<Duration>().as_minutes();
+    if needed > available {
+        return Err(ScheduleError::NotEnoughTime { needed, available });
+    }
+    let gaps = blocks.len() as u32 + 1;
+    let (base, extra) = ((available - needed) / gaps, (available - needed) % gaps);
+    let mut cursor = u32::from(start);
+    let mut placed = Vec::with_capacity(blocks.len());
+    for (i, block) in blocks.iter().enumerate() {
+        cursor += base + u32::from((i as u32) < extra);
+        let to_time = |minutes: u32| TimeOfDay::try_from(minutes as u16).map_err(|_| ScheduleError::OutOfDay);
+        let mut moved = (*block).clone();
+        moved.set_time(to_time(cursor)?, to_time(cursor + block.duration().as_minutes())?)?;
+        cursor += block.duration().as_minutes();
+        placed.push(moved);
+    }
+    Ok(placed)
+}
+
+/// 两个日程之间的差异
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleDiff {
+    /// 新增的时间段
+    pub added: Vec<Block>,
+    /// 删除的时间段
+    pub removed: Vec<Block>,
+    /// 修改的时间段：(旧, 新)
+    pub modified: Vec<(Block, Block)>,
+}
+
+impl ScheduleDiff {
+    /// 是否没有任何差异
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// 按分钟记录一天中每一分钟是否被占用的快照，由 `Schedule::occupancy` 生成
+///
+/// 生成后与日程无关联，日程修改后需要重新生成。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occupancy([bool; 24 * 60]);
+
+impl Occupancy {
+    /// 某分钟（午夜起）是否空闲，O(1)；超出一天范围时返回 false
+    pub fn is_free(&self, minute: u16) -> bool {
+        self.0.get(usize::from(minute)).is_some_and(|busy| !busy)
+    }
+    /// 某分钟（午夜起）是否被占用；超出一天范围时返回 false
+    pub fn is_busy(&self, minute: u16) -> bool {
+        self.0.get(usize::from(minute)).copied().unwrap_or(false)
+    }
+}
+
+/// 日程在某个窗口内的统计汇总，由 `Schedule::stats` 一次算出
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleStats {
+    /// 窗口内被占用的分钟数
+    pub busy_minutes: u32,
+    /// 窗口内空闲的分钟数
+    pub free_minutes: u32,
+    /// 与窗口相交的时间段数量
+    pub block_count: usize,
+    /// 其中固定时间段的数量
+    pub fixed_count: usize,
+    /// 最长的空闲时间段
+    pub largest_gap: Option<(TimeOfDay, TimeOfDay)>,
+    /// 安排完成度：窗口内已安排时间所占比例（0.0 到 1.0）
+    pub completion_rate: f32,
+}
+
+impl fmt::Display for Schedule {
+    /// 每行一个时间段，最后一行为合计
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for block in &self.blocks {
+            writeln!(f, "{}", block)?;
+        }
+        let (hours, minutes) = self.blocks.iter().map(|b| b.duration()).sum::<Duration>().hours_minutes();
+        match utils::locale() {
+            Locale::ZhCn => write!(f, "合计：{} 个时间段，共 {}小时{:02}分钟", self.blocks.len(), hours, minutes),
+            Locale::En => write!(f, "Total: {} blocks, {}h{:02}m", self.blocks.len(), hours, minutes),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Schedule {
+    type Item = &'a Block;
+    type IntoIter = std::slice::Iter<'a, Block>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.blocks.iter()
+    }
+}
+
+impl IntoIterator for Schedule {
+    type Item = Block;
+    type IntoIter = std::vec::IntoIter<Block>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.blocks.into_iter()
+    }
+}
+
+/// 表示操作 `Schedule` 时可能发生的错误。
+#[derive(Debug)]
+pub enum ScheduleError {
+    /// 新时间段与已有时间段重叠
+    Overlap { existing: Box<Block>, incoming: Box<Block> },
+    /// 索引越界
+    IndexOutOfBounds(usize),
+    /// 时间段超出一天的范围
+    OutOfDay,
+    /// 时间段本身不合法
+    Block(BlockError),
+    /// 分界时间必须严格递增
+    InvalidBoundaries,
+    /// 比例不在 [0, 1] 范围内
+    InvalidFraction(f32),
+    /// 未知的时区名称
+    UnknownTimeZone(String),
+    /// 未知的日程方案名称
+    UnknownPlan(String),
+    /// 文本解析失败（行号从 1 开始）
+    Parse { line: usize, reason: String },
+    /// 时间段短于日程要求的最短时长（分钟）
+    TooShort { min: u16, actual: u32 },
+    /// 窗口内的空闲时间不足以放下时间段（分钟）
+    NotEnoughTime { needed: u32, available: u32 },
+    /// 非固定时间段与固定时间段之间的缓冲不足（分钟）
+    BufferTooSmall { fixed: Box<Block>, block: Box<Block>, gap: u16, required: u16 },
+    /// 批量构建时第 `index` 个条目（从 0 开始）出错
+    Spec { index: usize, source: Box<ScheduleError> },
+    /// 读写文件失败
+    Io(std::io::Error),
+    /// JSON 格式不正确
+    Json(serde_json::Error),
+    /// 文件格式版本比程序支持的更新
+    UnsupportedVersion { found: u32, supported: u32 },
+}
+
+impl From<BlockError> for ScheduleError {
+    fn from(e: BlockError) -> ScheduleError {
+        ScheduleError::Block(e)
+    }
+}
+
+impl From<std::io::Error> for ScheduleError {
+    fn from(e: std::io::Error) -> ScheduleError {
+        ScheduleError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ScheduleError {
+    fn from(e: serde_json::Error) -> ScheduleError {
+        ScheduleError::Json(e)
+    }
+}
+
+impl fmt::Display for ScheduleError {
+    /// 按当前语言格式化错误信息
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, utils::locale()) {
+            (ScheduleError::Overlap { existing, incoming }, Locale::ZhCn) => {
+                write!(
+                    f,
+                    "时间段重叠：{}（{}-{}）与已有的 {}（{}-{}）冲突",
+                    incoming.name(),
+                    incoming.start_time(),
+                    incoming.end_time(),
+                    existing.name(),
+                    existing.start_time(),
+                    existing.end_time()
+                )
+            }
+            (ScheduleError::Overlap { existing, incoming }, Locale::En) => {
+                write!(
+                    f,
+                    "Overlap: {} ({}-{}) conflicts with existing {} ({}-{})",
+                    incoming.name(),
+                    incoming.start_time(),
+                    incoming.end_time(),
+                    existing.name(),
+                    existing.start_time(),
+                    existing.end_time()
+                )
+            }
+            (ScheduleError::IndexOutOfBounds(index), Locale::ZhCn) => {
+                write!(f, "索引越界：{}", index)
+            }
+            (ScheduleError::IndexOutOfBounds(index), Locale::En) => {
+                write!(f, "Index out of bounds: {}", index)
+            }
+            (ScheduleError::OutOfDay, Locale::ZhCn) => {
+                write!(f, "时间段超出一天的范围")
+            }
+            (ScheduleError::OutOfDay, Locale::En) => {
+                write!(f, "Block is outside of the day")
+            }
+            (ScheduleError::Block(e), _) => write!(f, "{}", e),
+            (ScheduleError::InvalidBoundaries, Locale::ZhCn) => {
+                write!(f, "分界时间必须严格递增")
+            }
+            (ScheduleError::InvalidBoundaries, Locale::En) => {
+                write!(f, "Boundaries must be strictly increasing")
+            }
+            (ScheduleError::InvalidFraction(fraction), Locale::ZhCn) => {
+                write!(f, "比例必须在 [0, 1] 范围内：{}", fraction)
+            }
+            (ScheduleError::InvalidFraction(fraction), Locale::En) => {
+                write!(f, "Fraction must be within [0, 1]: {}", fraction)
+            }
+            (ScheduleError::UnknownTimeZone(tz), Locale::ZhCn) => {
+                write!(f, "未知的时区：{}", tz)
+            }
+            (ScheduleError::UnknownTimeZone(tz), Locale::En) => {
+                write!(f, "Unknown time zone: {}", tz)
+            }
+            (ScheduleError::UnknownPlan(name), Locale::ZhCn) => {
+                write!(f, "未知的日程方案：{}", name)
+            }
+            (ScheduleError::UnknownPlan(name), Locale::En) => {
+                write!(f, "Unknown plan: {}", name)
+            }
+            (ScheduleError::Parse { line, reason }, Locale::ZhCn) => {
+                write!(f, "第 {} 行解析失败：{}", line, reason)
+            }
+            (ScheduleError::Parse { line, reason }, Locale::En) => {
+                write!(f, "Failed to parse line {}: {}", line, reason)
+            }
+            (ScheduleError::TooShort { min, actual }, Locale::ZhCn) => {
+                write!(f, "时间段过短：{} 分钟，至少需要 {} 分钟", actual, min)
+            }
+            (ScheduleError::TooShort { min, actual }, Locale::En) => {
+                write!(f, "Block is too short: {} minutes, at least {} required", actual, min)
+            }
+            (ScheduleError::NotEnoughTime { needed, available }, Locale::ZhCn) => {
+                write!(f, "时间不足：需要 {} 分钟，只有 {} 分钟", needed, available)
+            }
+            (ScheduleError::NotEnoughTime { needed, available }, Locale::En) => {
+                write!(f, "Not enough time: {} minutes needed, only {} available", needed, available)
+            }
+            (ScheduleError::BufferTooSmall { fixed, block, gap, required }, Locale::ZhCn) => {
+                write!(
+                    f,
+                    "缓冲不足：{}（{}-{}）与固定时间段 {}（{}-{}）只间隔 {} 分钟，至少需要 {} 分钟",
+                    block.name(),
+                    block.start_time(),
+                    block.end_time(),
+                    fixed.name(),
+                    fixed.start_time(),
+                    fixed.end_time(),
+                    gap,
+                    required
+                )
+            }
+            (ScheduleError::BufferTooSmall { fixed, block, gap, required }, Locale::En) => {
+                write!(
+                    f,
+                    "Buffer too small: {} ({}-{}) is only {} minutes from fixed block {} ({}-{}), at least {} required",
+                    block.name(),
+                    block.start_time(),
+                    block.end_time(),
+                    gap,
+                    fixed.name(),
+                    fixed.start_time(),
+                    fixed.end_time(),
+                    required
+                )
+            }
+            (ScheduleError::Spec { index, source }, Locale::ZhCn) => {
+                write!(f, "第 {} 个条目无效：{}", index, source)
+            }
+            (ScheduleError::Spec { index, source }, Locale::En) => {
+                write!(f, "Entry {} is invalid: {}", index, source)
+            }
+            (ScheduleError::Io(e), Locale::ZhCn) => write!(f, "读写文件失败：{}", e),
+            (ScheduleError::Io(e), Locale::En) => write!(f, "Failed to read or write file: {}", e),
+            (ScheduleError::Json(e), Locale::ZhCn) => write!(f, "JSON 格式不正确：{}", e),
+            (ScheduleError::Json(e), Locale::En) => write!(f, "Invalid JSON: {}", e),
+            (ScheduleError::UnsupportedVersion { found, supported }, Locale::ZhCn) => {
+                write!(f, "文件格式版本 {} 过新，当前程序最高支持版本 {}，请升级程序", found, supported)
+            }
+            (ScheduleError::UnsupportedVersion { found, supported }, Locale::En) => {
+                write!(
+                    f,
+                    "File format version {} is too new, this program supports up to version {}; please upgrade",
+                    found, supported
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(start: (u16, u16), end: (u16, u16), name: &str) -> Block {
+        Block::builder()
+            .start_time(TimeOfDay::new(start.0, start.1).unwrap())
+            .end_time(TimeOfDay::new(end.0, end.1).unwrap())
+            .name(name.to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_add_block_keeps_sorted() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((14, 0), (15, 0), "下午")).unwrap();
+        schedule.add_block(block((9, 0), (10, 0), "上午")).unwrap();
+
+        let names: Vec<&str> = schedule.blocks().iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["上午", "下午"]);
+    }
+
+    #[test]
+    fn test_add_block_overlap() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+
+        let result = schedule.add_block(block((9, 30), (10, 30), "冲突"));
+        assert!(matches!(result, Err(ScheduleError::Overlap { .. })));
+        assert_eq!(schedule.len(), 1);
+
+        // 首尾相接不算重叠
+        schedule.add_block(block((10, 0), (11, 0), "相接")).unwrap();
+        assert_eq!(schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_remove_block() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+
+        assert!(matches!(schedule.remove_block(1), Err(ScheduleError::IndexOutOfBounds(1))));
+        let removed = schedule.remove_block(0).unwrap();
+        assert_eq!(removed.name(), "会议");
+        assert!(schedule.is_empty());
+    }
+
+    #[test]
+    fn test_busiest_hour_empty() {
+        assert_eq!(Schedule::new().busiest_hour(), None);
+    }
+
+    #[test]
+    fn test_busiest_hour_split_across_hours() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 30), (10, 30), "跨整点")).unwrap();
+
+        // 9 点和 10 点各占 30 分钟，并列时取较早的小时
+        assert_eq!(schedule.busiest_hour(), Some((9, 30)));
+
+        schedule.add_block(block((10, 45), (11, 0), "短会")).unwrap();
+        assert_eq!(schedule.busiest_hour(), Some((10, 45)));
+    }
+
+    fn routine() -> Template {
+        Template::new("作息".to_string())
+            .with_block(block((7, 0), (8, 0), "早餐"))
+            .with_block(block((12, 0), (13, 0), "午饭"))
+    }
+
+    #[test]
+    fn test_from_template() {
+        let schedule = Schedule::from_template(&routine()).unwrap();
+        let names: Vec<&str> = schedule.blocks().iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["早餐", "午饭"]);
+
+        // 模板自身重叠时报错
+        let broken = routine().with_block(block((12, 30), (13, 30), "冲突"));
+        assert!(matches!(Schedule::from_template(&broken), Err(ScheduleError::Overlap { .. })));
+    }
+
+    #[test]
+    fn test_apply_template_without_skipping() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((12, 30), (14, 0), "会议")).unwrap();
+
+        let result = schedule.apply_template(&routine(), false);
+        assert!(matches!(result, Err(ScheduleError::Overlap { .. })));
+        // 出错时日程保持不变
+        assert_eq!(schedule.len(), 1);
+    }
+
+    #[test]
+    fn test_apply_template_skipping_conflicts() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((12, 30), (14, 0), "会议")).unwrap();
+
+        let applied = schedule.apply_template(&routine(), true).unwrap();
+        assert_eq!(applied, 1);
+        let names: Vec<&str> = schedule.blocks().iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["早餐", "会议"]);
+    }
+
+    fn t(hour: u16, minute: u16) -> TimeOfDay {
+        TimeOfDay::new(hour, minute).unwrap()
+    }
+
+    #[test]
+    fn test_free_gaps() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "讨论")).unwrap();
+
+        let gaps = schedule.free_gaps(t(8, 0), t(18, 0));
+        assert_eq!(*gaps, vec![(t(8, 0), t(9, 0)), (t(10, 0), t(11, 0)), (t(12, 0), t(18, 0))]);
+
+        // 窗口边界裁剪时间段
+        let gaps = schedule.free_gaps(t(9, 30), t(11, 30));
+        assert_eq!(*gaps, vec![(t(10, 0), t(11, 0))]);
+    }
+
+    #[test]
+    fn test_free_gaps_cache_invalidation() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        assert_eq!(*schedule.free_gaps(t(8, 0), t(12, 0)), vec![(t(8, 0), t(9, 0)), (t(10, 0), t(12, 0))]);
+
+        schedule.add_block(block((10, 30), (11, 0), "电话")).unwrap();
+        let after_add = vec![(t(8, 0), t(9, 0)), (t(10, 0), t(10, 30)), (t(11, 0), t(12, 0))];
+        assert_eq!(*schedule.free_gaps(t(8, 0), t(12, 0)), after_add);
+
+        schedule.remove_block(0).unwrap();
+        assert_eq!(*schedule.free_gaps(t(8, 0), t(12, 0)), vec![(t(8, 0), t(10, 30)), (t(11, 0), t(12, 0))]);
+
+        // 撤销、重做与批量修改同样使缓存失效
+        assert!(schedule.undo());
+        assert_eq!(*schedule.free_gaps(t(8, 0), t(12, 0)), after_add);
+        schedule.retain(|b| b.name() == "会议");
+        assert_eq!(*schedule.free_gaps(t(8, 0), t(12, 0)), vec![(t(8, 0), t(9, 0)), (t(10, 0), t(12, 0))]);
+
+        // 换一个窗口不会命中旧窗口的结果
+        assert_eq!(*schedule.free_gaps(t(9, 30), t(12, 0)), vec![(t(10, 0), t(12, 0))]);
+
+        // 命中缓存时共享同一份结果
+        let first = schedule.free_gaps(t(9, 30), t(12, 0));
+        assert!(Arc::ptr_eq(&first, &schedule.free_gaps(t(9, 30), t(12, 0))));
+    }
+
+    #[test]
+    fn test_largest_gap() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((12, 0), (13, 0), "午饭")).unwrap();
+
+        // 多个空闲：8-9、10-12、13-14，最长的是 10-12
+        assert_eq!(schedule.largest_gap(t(8, 0), t(14, 0)), Some((t(10, 0), t(12, 0))));
+
+        // 并列：8-9 与 10-11 同长，取较早的
+        assert_eq!(schedule.largest_gap(t(8, 0), t(11, 0)), Some((t(8, 0), t(9, 0))));
+    }
+
+    #[test]
+    fn test_largest_gap_fully_booked() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "讨论")).unwrap();
+
+        assert_eq!(schedule.largest_gap(t(9, 0), t(11, 0)), None);
+    }
+
+    #[test]
+    fn test_density() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 0), (10, 30), "讨论")).unwrap();
+
+        let density = schedule.density(60, 30);
+        assert_eq!(density.len(), 48);
+        let at = |time: TimeOfDay| density.iter().find(|(t, _)| *t == time).unwrap().1;
+        // 9:00-10:00 完全占用
+        assert_eq!(at(t(9, 0)), 1.0);
+        // 9:30-10:30 完全占用（两个相接的时间段）
+        assert_eq!(at(t(9, 30)), 1.0);
+        // 10:00-11:00 占用一半
+        assert_eq!(at(t(10, 0)), 0.5);
+        // 空闲区域
+        assert_eq!(at(t(14, 0)), 0.0);
+    }
+
+    #[test]
+    fn test_density_rejects_zero() {
+        let schedule = Schedule::new();
+        assert!(schedule.density(0, 30).is_empty());
+        assert!(schedule.density(60, 0).is_empty());
+    }
+
+    #[test]
+    fn test_shift_block() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "讨论")).unwrap();
+
+        schedule.shift_block(0, 30).unwrap();
+        assert_eq!(schedule.blocks()[0].start_time(), t(9, 30));
+        assert_eq!(schedule.blocks()[0].end_time(), t(10, 30));
+
+        // 与后一个时间段重叠
+        assert!(matches!(schedule.shift_block(0, 60), Err(ScheduleError::Overlap { .. })));
+        // 超出一天范围
+        assert!(matches!(schedule.shift_block(0, -600), Err(ScheduleError::OutOfDay)));
+        assert_eq!(schedule.blocks()[0].start_time(), t(9, 30));
+    }
+
+    #[test]
+    fn test_move_block_to() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 30), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "讨论")).unwrap();
+
+        schedule.move_block_to(0, t(14, 0)).unwrap();
+        assert_eq!(schedule.blocks()[1], block((14, 0), (15, 30), "会议"));
+        assert_eq!(schedule.blocks()[0].name(), "讨论");
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[0], block((9, 0), (10, 30), "会议"));
+    }
+
+    #[test]
+    fn test_move_block_to_conflict_rolls_back() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 30), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "讨论")).unwrap();
+
+        assert!(matches!(schedule.move_block_to(0, t(10, 0)), Err(ScheduleError::Overlap { .. })));
+        assert!(matches!(schedule.move_block_to(0, t(23, 0)), Err(ScheduleError::OutOfDay)));
+        assert!(matches!(schedule.move_block_to(5, t(13, 0)), Err(ScheduleError::IndexOutOfBounds(5))));
+        assert_eq!(schedule.blocks()[0], block((9, 0), (10, 30), "会议"));
+        assert_eq!(schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_move_fixed_block_to() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(fixed_block((9, 0), (10, 0), "固定会议")).unwrap();
+
+        let result = schedule.move_block_to(0, t(13, 0));
+        assert!(matches!(result, Err(ScheduleError::Block(BlockError::FixedBlockTimeChange))));
+        assert_eq!(schedule.blocks()[0].start_time(), t(9, 0));
+    }
+
+    #[test]
+    fn test_undo_add_block() {
+        let mut schedule = Schedule::new();
+        assert!(!schedule.undo());
+
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        assert!(schedule.undo());
+        assert!(schedule.is_empty());
+        assert!(!schedule.undo());
+    }
+
+    #[test]
+    fn test_redo_after_undo() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.shift_block(0, 60).unwrap();
+        schedule.remove_block(0).unwrap();
+
+        // 依次撤销删除和平移
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[0].start_time(), t(10, 0));
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[0].start_time(), t(9, 0));
+
+        // 重做平移
+        assert!(schedule.redo());
+        assert_eq!(schedule.blocks()[0].start_time(), t(10, 0));
+
+        // 新的编辑会清空重做历史
+        schedule.add_block(block((12, 0), (13, 0), "午饭")).unwrap();
+        assert!(!schedule.redo());
+    }
+
+    #[test]
+    fn test_undo_depth_is_bounded() {
+        let mut schedule = Schedule::new().with_max_undo_depth(2);
+        schedule.add_block(block((9, 0), (10, 0), "一")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "二")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "三")).unwrap();
+
+        assert!(schedule.undo());
+        assert!(schedule.undo());
+        assert!(!schedule.undo());
+        assert_eq!(schedule.len(), 1);
+        assert_eq!(schedule.blocks()[0].name(), "一");
+    }
+
+    #[test]
+    fn test_remove_by_name() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "休息")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "会议")).unwrap();
+
+        // 不存在的名称
+        assert_eq!(schedule.remove_by_name("午饭"), 0);
+        // 唯一的名称
+        assert_eq!(schedule.remove_by_name("休息"), 1);
+        // 两个同名时间段
+        assert_eq!(schedule.remove_by_name("会议"), 2);
+        assert!(schedule.is_empty());
+
+        // 一次删除只需一次撤销
+        assert!(schedule.undo());
+        assert_eq!(schedule.len(), 2);
+        assert!(schedule.blocks().iter().all(|b| b.name() == "会议"));
+        assert!(schedule.undo());
+        assert_eq!(schedule.len(), 3);
+    }
+
+    #[test]
+    fn test_remove_by_name_ignore_case() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "Standup")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "standup")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "Review")).unwrap();
+
+        assert_eq!(schedule.remove_by_name("STANDUP"), 0);
+        assert_eq!(schedule.remove_by_name_ignore_case("STANDUP"), 2);
+        assert_eq!(schedule.len(), 1);
+        assert_eq!(schedule.blocks()[0].name(), "Review");
+    }
+
+    #[test]
+    fn test_iter_and_into_iter() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 30), "会议")).unwrap();
+        schedule.add_block(block((14, 0), (14, 45), "讨论")).unwrap();
+
+        let total = schedule.iter().map(|b| b.duration()).sum::<Duration>();
+        assert_eq!(total, Duration::from_minutes(135));
+
+        let mut names = Vec::new();
+        for b in &schedule {
+            names.push(b.name().to_string());
+        }
+        assert_eq!(names, vec!["会议", "讨论"]);
+
+        let owned: Vec<Block> = schedule.into_iter().collect();
+        assert_eq!(owned.len(), 2);
+    }
+
+    #[test]
+    fn test_display() {
+        let _locale = utils::lock_locale();
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 30), "会议")).unwrap();
+        schedule.add_block(block((14, 0), (14, 45), "讨论")).unwrap();
+
+        let output = format!("{}", schedule);
+        assert!(output.contains("09:00-10:30 会议\n"));
+        assert!(output.contains("14:00-14:45 讨论\n"));
+        assert!(output.ends_with("合计：2 个时间段，共 2小时15分钟"));
+    }
+
+    #[test]
+    fn test_display_locale() {
+        let _locale = utils::lock_locale();
+        utils::set_locale(Locale::En);
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 30), "会议")).unwrap();
+        assert!(format!("{}", schedule).ends_with("Total: 1 blocks, 1h30m"));
+    }
+
+    #[test]
+    fn test_error_display_locale() {
+        let _locale = utils::lock_locale();
+        let error = ScheduleError::TooShort { min: 15, actual: 5 };
+        utils::set_locale(Locale::En);
+        assert_eq!(error.to_string(), "Block is too short: 5 minutes, at least 15 required");
+        utils::set_locale(Locale::ZhCn);
+        assert_eq!(error.to_string(), "时间段过短：5 分钟，至少需要 15 分钟");
+    }
+
+    fn fixed_block(start: (u16, u16), end: (u16, u16), name: &str) -> Block {
+        let mut b = block(start, end, name);
+        b.set_is_fixed(true);
+        b
+    }
+
+    #[test]
+    fn test_compact_to_contiguous_morning() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "一")).unwrap();
+        schedule.add_block(block((11, 0), (11, 30), "二")).unwrap();
+        schedule.add_block(block((14, 0), (15, 0), "三")).unwrap();
+
+        schedule.compact(t(8, 0)).unwrap();
+        let spans: Vec<(TimeOfDay, TimeOfDay)> =
+            schedule.iter().map(|b| (b.start_time(), b.end_time())).collect();
+        assert_eq!(spans, vec![(t(8, 0), t(9, 0)), (t(9, 0), t(9, 30)), (t(9, 30), t(10, 30))]);
+
+        // 可以整体撤销
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[0].start_time(), t(9, 0));
+    }
+
+    #[test]
+    fn test_compact_around_fixed_block() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((8, 0), (8, 30), "一")).unwrap();
+        schedule.add_block(fixed_block((9, 0), (10, 0), "固定会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "二")).unwrap();
+        schedule.add_block(block((13, 0), (13, 30), "三")).unwrap();
+
+        schedule.compact(t(8, 0)).unwrap();
+        let spans: Vec<(&str, TimeOfDay, TimeOfDay)> =
+            schedule.iter().map(|b| (b.name(), b.start_time(), b.end_time())).collect();
+        // "二" 放不进 8:30-9:00 的空隙，只能排在固定会议之后
+        assert_eq!(
+            spans,
+            vec![
+                ("一", t(8, 0), t(8, 30)),
+                ("固定会议", t(9, 0), t(10, 0)),
+                ("二", t(10, 0), t(11, 0)),
+                ("三", t(11, 0), t(11, 30)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut original = Schedule::new();
+        original.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        original.add_block(block((10, 0), (11, 0), "讨论")).unwrap();
+        original.add_block(block((14, 0), (15, 0), "写代码")).unwrap();
+
+        let mut changed = original.clone();
+        changed.remove_by_name("讨论");
+        changed.add_block(block((16, 0), (17, 0), "复盘")).unwrap();
+        let mut modified = changed.remove_block(0).unwrap();
+        modified.set_description(Some("改了描述".to_string())).unwrap();
+        changed.add_block(modified.clone()).unwrap();
+
+        let diff = original.diff(&changed);
+        assert_eq!(diff.added.iter().map(|b| b.name()).collect::<Vec<_>>(), vec!["复盘"]);
+        assert_eq!(diff.removed.iter().map(|b| b.name()).collect::<Vec<_>>(), vec!["讨论"]);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].0, original.blocks()[0]);
+        assert_eq!(diff.modified[0].1, modified);
+
+        assert!(original.diff(&original.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_earliest_ending() {
+        let mut schedule = Schedule::new();
+        assert!(schedule.earliest_ending().is_none());
+
+        schedule.add_block(block((14, 0), (15, 0), "下午")).unwrap();
+        schedule.add_block(block((9, 0), (10, 0), "上午")).unwrap();
+        assert_eq!(schedule.earliest_ending().unwrap().name(), "上午");
+    }
+
+    /// 直接构造未经校验的日程，模拟导入的原始数据
+    fn raw_schedule(blocks: Vec<Block>) -> Schedule {
+        Schedule { blocks, ..Schedule::new() }
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "讨论")).unwrap();
+        assert!(schedule.find_duplicates().is_empty());
+
+        // 一对重复
+        let pair = raw_schedule(vec![
+            block((9, 0), (10, 0), "会议"),
+            block((9, 0), (10, 0), "会议"),
+            block((10, 0), (11, 0), "讨论"),
+        ]);
+        assert_eq!(pair.find_duplicates(), vec![vec![0, 1]]);
+
+        // 三个重复，外加一组同时段但不同名称的不算重复
+        let triple = raw_schedule(vec![
+            block((9, 0), (10, 0), "会议"),
+            block((9, 0), (10, 0), "讨论"),
+            block((9, 0), (10, 0), "会议"),
+            block((9, 0), (10, 0), "会议"),
+        ]);
+        assert_eq!(triple.find_duplicates(), vec![vec![0, 2, 3]]);
+    }
+
+    #[test]
+    fn test_reschedule_block() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "讨论")).unwrap();
+
+        // 正常改期，并重新排序
+        schedule.reschedule_block(0, t(13, 0), t(14, 30)).unwrap();
+        assert_eq!(schedule.blocks()[0].name(), "讨论");
+        assert_eq!(schedule.blocks()[1].name(), "会议");
+        assert_eq!(schedule.blocks()[1].end_time(), t(14, 30));
+    }
+
+    #[test]
+    fn test_reschedule_block_conflict_rolls_back() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "讨论")).unwrap();
+
+        let result = schedule.reschedule_block(0, t(10, 30), t(11, 30));
+        assert!(matches!(result, Err(ScheduleError::Overlap { .. })));
+        assert_eq!(schedule.blocks()[0].start_time(), t(9, 0));
+        assert_eq!(schedule.blocks()[0].end_time(), t(10, 0));
+        assert_eq!(schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_reschedule_fixed_block() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(fixed_block((9, 0), (10, 0), "固定会议")).unwrap();
+
+        let result = schedule.reschedule_block(0, t(13, 0), t(14, 0));
+        assert!(matches!(result, Err(ScheduleError::Block(BlockError::FixedBlockTimeChange))));
+        assert_eq!(schedule.blocks()[0].start_time(), t(9, 0));
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(fixed_block((9, 0), (10, 0), "周会")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "写代码")).unwrap();
+        schedule.add_block(fixed_block((14, 0), (15, 0), "代码评审")).unwrap();
+
+        let fixed = schedule.filter(|b| b.is_fixed());
+        let names: Vec<&str> = fixed.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["周会", "代码评审"]);
+
+        let code = schedule.filter(|b| b.name().contains("代码"));
+        let names: Vec<&str> = code.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["写代码", "代码评审"]);
+
+        // 原日程不变
+        assert_eq!(schedule.len(), 3);
+    }
+
+    #[test]
+    fn test_snap_time() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "讨论")).unwrap();
+
+        // 吸附到附近的结束边界
+        assert_eq!(schedule.snap_time(t(10, 4), 5), t(10, 0));
+        // 吸附到附近的开始边界（恰好在阈值上）
+        assert_eq!(schedule.snap_time(t(10, 55), 5), t(11, 0));
+        // 阈值内没有边界，保持不动
+        assert_eq!(schedule.snap_time(t(10, 30), 5), t(10, 30));
+        assert_eq!(Schedule::new().snap_time(t(10, 30), 60), t(10, 30));
+    }
+
+    #[test]
+    fn test_busy_fraction_and_overbooked() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (11, 0), "会议")).unwrap();
+
+        // 8:00-16:00 共 8 小时，占用 2 小时
+        assert_eq!(schedule.busy_fraction(t(8, 0), t(16, 0)), 0.25);
+        assert!(!schedule.is_overbooked(t(8, 0), t(16, 0), 0.5).unwrap());
+
+        schedule.add_block(block((12, 0), (15, 0), "写代码")).unwrap();
+        assert_eq!(schedule.busy_fraction(t(8, 0), t(16, 0)), 0.625);
+        assert!(schedule.is_overbooked(t(8, 0), t(16, 0), 0.5).unwrap());
+
+        // 只统计窗口内的部分
+        assert_eq!(schedule.busy_fraction(t(10, 0), t(12, 0)), 0.5);
+    }
+
+    #[test]
+    fn test_is_overbooked_rejects_invalid_fraction() {
+        let schedule = Schedule::new();
+        let result = schedule.is_overbooked(t(8, 0), t(16, 0), 1.5);
+        assert!(matches!(result, Err(ScheduleError::InvalidFraction(f)) if f == 1.5));
+        assert!(matches!(schedule.is_overbooked(t(8, 0), t(16, 0), -0.1), Err(ScheduleError::InvalidFraction(_))));
+        assert!(matches!(schedule.is_overbooked(t(8, 0), t(16, 0), f32::NAN), Err(ScheduleError::InvalidFraction(_))));
+        // 边界值合法
+        assert_eq!(schedule.is_overbooked(t(8, 0), t(16, 0), 0.0).ok(), Some(false));
+        assert_eq!(schedule.is_overbooked(t(8, 0), t(16, 0), 1.0).ok(), Some(false));
+    }
+
+    fn merge_fixture() -> (Schedule, Schedule) {
+        let mut existing = Schedule::new();
+        existing.add_block(block((9, 0), (10, 0), "已有会议")).unwrap();
+        existing.add_block(block((13, 0), (14, 0), "午休")).unwrap();
+
+        let mut incoming = Schedule::new();
+        incoming.add_block(block((9, 30), (10, 30), "新会议")).unwrap();
+        incoming.add_block(block((15, 0), (16, 0), "新任务")).unwrap();
+        (existing, incoming)
+    }
+
+    #[test]
+    fn test_merge_keep_existing() {
+        let (mut schedule, incoming) = merge_fixture();
+        schedule.merge(incoming, MergeStrategy::KeepExisting).unwrap();
+
+        let names: Vec<&str> = schedule.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["已有会议", "午休", "新任务"]);
+    }
+
+    #[test]
+    fn test_merge_prefer_incoming() {
+        let (mut schedule, incoming) = merge_fixture();
+        schedule.merge(incoming, MergeStrategy::PreferIncoming).unwrap();
+
+        let names: Vec<&str> = schedule.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["新会议", "午休", "新任务"]);
+    }
+
+    #[test]
+    fn test_merge_error() {
+        let (mut schedule, incoming) = merge_fixture();
+        let result = schedule.merge(incoming, MergeStrategy::Error);
+
+        match result {
+            Err(ScheduleError::Overlap { existing, incoming }) => {
+                assert_eq!(existing.name(), "已有会议");
+                assert_eq!(incoming.name(), "新会议");
+            }
+            other => panic!("应该返回重叠错误：{:?}", other),
+        }
+        // 日程保持不变
+        let names: Vec<&str> = schedule.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["已有会议", "午休"]);
+    }
+
+    #[test]
+    fn test_index_of_time() {
+        let mut schedule = Schedule::new();
+        assert_eq!(schedule.index_of_time(t(9, 0)), 0);
+
+        schedule.add_block(block((9, 0), (10, 0), "一")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "二")).unwrap();
+        assert_eq!(schedule.index_of_time(t(8, 0)), 0);
+        assert_eq!(schedule.index_of_time(t(9, 0)), 1);
+        assert_eq!(schedule.index_of_time(t(10, 30)), 1);
+        assert_eq!(schedule.index_of_time(t(13, 0)), 2);
+    }
+
+    #[test]
+    fn test_many_inserts_stay_sorted() {
+        let mut schedule = Schedule::new();
+        // 以打乱的顺序插入 0 点到 22 点的时间段
+        let hours: Vec<u16> = (0..23).map(|i| (i * 7) % 23).collect();
+        for &hour in &hours {
+            schedule.add_block(block((hour, 0), (hour, 59), &hour.to_string())).unwrap();
+        }
+        assert_eq!(schedule.len(), 23);
+        assert!(schedule.blocks().windows(2).all(|w| w[0].end_time() <= w[1].start_time()));
+        assert_eq!(schedule.blocks()[0].start_time(), t(0, 0));
+        assert_eq!(schedule.blocks()[22].start_time(), t(22, 0));
+    }
+
+    #[test]
+    fn test_add_block_detects_neighbor_conflicts() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "一")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "二")).unwrap();
+        schedule.add_block(block((13, 0), (14, 0), "三")).unwrap();
+
+        // 与前一个相邻时间段冲突
+        let result = schedule.add_block(block((11, 30), (12, 30), "冲突前"));
+        assert!(matches!(result, Err(ScheduleError::Overlap { existing, .. }) if existing.name() == "二"));
+        // 与后一个相邻时间段冲突
+        let result = schedule.add_block(block((12, 30), (13, 30), "冲突后"));
+        assert!(matches!(result, Err(ScheduleError::Overlap { existing, .. }) if existing.name() == "三"));
+        // 覆盖多个时间段
+        let result = schedule.add_block(block((8, 0), (15, 0), "覆盖"));
+        assert!(matches!(result, Err(ScheduleError::Overlap { .. })));
+        // 同一开始时间
+        let result = schedule.add_block(block((9, 0), (9, 30), "同时开始"));
+        assert!(matches!(result, Err(ScheduleError::Overlap { .. })));
+
+        assert_eq!(schedule.len(), 3);
+    }
+
+    #[test]
+    fn test_split_day() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "晨会")).unwrap();
+        schedule.add_block(block((11, 0), (13, 30), "跨午")).unwrap();
+        schedule.add_block(block((19, 0), (20, 0), "晚课")).unwrap();
+
+        let segments = schedule.split_day(&[t(12, 0), t(18, 0)]).unwrap();
+        assert_eq!(segments.len(), 3);
+
+        let spans = |s: &Schedule| -> Vec<(String, TimeOfDay, TimeOfDay)> {
+            s.iter().map(|b| (b.name().to_string(), b.start_time(), b.end_time())).collect()
+        };
+        assert_eq!(
+            spans(&segments[0]),
+            vec![("晨会".to_string(), t(9, 0), t(10, 0)), ("跨午".to_string(), t(11, 0), t(12, 0))]
+        );
+        assert_eq!(spans(&segments[1]), vec![("跨午".to_string(), t(12, 0), t(13, 30))]);
+        assert_eq!(spans(&segments[2]), vec![("晚课".to_string(), t(19, 0), t(20, 0))]);
+    }
+
+    #[test]
+    fn test_split_day_invalid_boundaries() {
+        let schedule = Schedule::new();
+        assert!(matches!(schedule.split_day(&[t(18, 0), t(12, 0)]), Err(ScheduleError::InvalidBoundaries)));
+        assert!(matches!(schedule.split_day(&[t(12, 0), t(12, 0)]), Err(ScheduleError::InvalidBoundaries)));
+    }
+
+    #[test]
+    fn test_from_specs() {
+        let schedule = Schedule::from_specs(&[
+            (t(9, 0), t(10, 0), "会议"),
+            (t(12, 0), t(13, 0), "午饭"),
+            (t(10, 0), t(11, 30), "写代码"),
+        ])
+        .unwrap();
+        let names: Vec<&str> = schedule.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["会议", "写代码", "午饭"]);
+        assert!(!schedule.can_undo());
+    }
+
+    #[test]
+    fn test_from_specs_reports_failing_index() {
+        let result = Schedule::from_specs(&[
+            (t(9, 0), t(10, 0), "会议"),
+            (t(12, 0), t(13, 0), "午饭"),
+            (t(12, 30), t(14, 0), "散步"),
+            (t(15, 0), t(14, 0), "不会被检查"),
+        ]);
+        match result {
+            Err(ScheduleError::Spec { index, source }) => {
+                assert_eq!(index, 2);
+                assert!(matches!(*source, ScheduleError::Overlap { .. }));
+            }
+            other => panic!("应报告第 2 个条目重叠，实际为 {:?}", other.map(|s| s.len())),
+        }
+
+        let result = Schedule::from_specs(&[(t(10, 0), t(9, 0), "倒序")]);
+        assert!(matches!(
+            result,
+            Err(ScheduleError::Spec { index: 0, source }) if matches!(*source, ScheduleError::Block(BlockError::InvalidTime { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_min_duration() {
+        let mut schedule = Schedule::new().with_min_duration(5);
+        // 恰好等于最短时长
+        schedule.add_block(block((9, 0), (9, 5), "站会")).unwrap();
+        // 短于最短时长
+        let result = schedule.add_block(block((10, 0), (10, 4), "喝水"));
+        assert!(matches!(result, Err(ScheduleError::TooShort { min: 5, actual: 4 })));
+        assert_eq!(schedule.len(), 1);
+        // 调整时间同样受限制
+        let result = schedule.reschedule_block(0, t(9, 0), t(9, 3));
+        assert!(matches!(result, Err(ScheduleError::TooShort { min: 5, actual: 3 })));
+        assert_eq!(schedule.blocks()[0].end_time(), t(9, 5));
+    }
+
+    #[test]
+    fn test_merge_respects_min_duration() {
+        let mut schedule = Schedule::new().with_min_duration(5);
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        let mut incoming = Schedule::new();
+        incoming.add_block(block((11, 0), (11, 3), "喝水")).unwrap();
+
+        let result = schedule.merge(incoming, MergeStrategy::KeepExisting);
+        assert!(matches!(result, Err(ScheduleError::TooShort { min: 5, actual: 3 })));
+        assert_eq!(schedule.len(), 1);
+    }
+
+    #[test]
+    fn test_min_duration_default_unlimited() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((10, 0), (10, 1), "喝水")).unwrap();
+        assert_eq!(schedule.len(), 1);
+    }
+
+    #[test]
+    fn test_try_from_blocks() {
+        let schedule = Schedule::try_from_blocks(vec![
+            block((14, 0), (15, 0), "下午"),
+            block((9, 0), (10, 0), "上午"),
+            block((10, 0), (11, 0), "相接"),
+        ])
+        .unwrap();
+        let names: Vec<&str> = schedule.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["上午", "相接", "下午"]);
+        assert!(!schedule.can_undo());
+    }
+
+    #[test]
+    fn test_try_from_blocks_overlap() {
+        let result = Schedule::try_from_blocks(vec![
+            block((14, 0), (15, 0), "下午"),
+            block((9, 0), (10, 0), "上午"),
+            block((14, 30), (16, 0), "冲突"),
+        ]);
+        match result {
+            Err(ScheduleError::Overlap { existing, incoming }) => {
+                assert_eq!(existing.name(), "下午");
+                assert_eq!(incoming.name(), "冲突");
+            }
+            other => panic!("应返回重叠错误，实际为 {:?}", other.map(|s| s.len())),
+        }
+    }
+
+    #[test]
+    fn test_on_change_events() {
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut schedule = Schedule::new();
+        let sink = Arc::clone(&events);
+        schedule.on_change(Box::new(move |event| sink.lock().unwrap().push(event.clone())));
+
+        let morning = block((9, 0), (10, 0), "上午");
+        let moved = block((9, 30), (10, 30), "上午");
+        let lunch = block((12, 0), (13, 0), "午饭");
+        schedule.add_block(morning.clone()).unwrap();
+        schedule.add_block(lunch.clone()).unwrap();
+        schedule.shift_block(0, 30).unwrap();
+        schedule.remove_block(1).unwrap();
+        schedule.undo();
+        // 失败的操作不产生事件
+        assert!(schedule.add_block(block((9, 0), (11, 0), "冲突")).is_err());
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ScheduleEvent::Added(morning.clone()),
+                ScheduleEvent::Added(lunch.clone()),
+                ScheduleEvent::Rescheduled {
+                    before: morning,
+                    after: moved,
+                },
+                ScheduleEvent::Removed(lunch.clone()),
+                ScheduleEvent::Added(lunch),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_on_change_bulk_and_template() {
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut schedule = Schedule::new();
+        let sink = Arc::clone(&events);
+        schedule.on_change(Box::new(move |event| sink.lock().unwrap().push(event.clone())));
+
+        let template = Template::new("上午".to_string()).with_block(block((10, 0), (11, 0), "学习"));
+        schedule.apply_template(&template, false).unwrap();
+        schedule.compact(t(9, 0)).unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ScheduleEvent::Added(block((10, 0), (11, 0), "学习")),
+                ScheduleEvent::Removed(block((10, 0), (11, 0), "学习")),
+                ScheduleEvent::Added(block((9, 0), (10, 0), "学习")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_progress_at() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((12, 0), (13, 0), "午饭")).unwrap();
+
+        assert_eq!(schedule.progress_at(t(12, 0)), vec![(1, 0.0)]);
+        assert_eq!(schedule.progress_at(t(9, 30)), vec![(0, 0.5)]);
+        assert!(schedule.progress_at(t(11, 0)).is_empty());
+        // 结束时间不属于该时间段
+        assert!(schedule.progress_at(t(10, 0)).is_empty());
+    }
+
+    fn priority_block(start: (u16, u16), end: (u16, u16), name: &str, priority: u8) -> Block {
+        Block::builder()
+            .start_time(t(start.0, start.1))
+            .end_time(t(end.0, end.1))
+            .name(name.to_string())
+            .is_fixed(false)
+            .priority(priority)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_add_block_priority_evicts_lower() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(priority_block((9, 0), (10, 0), "整理邮件", 1)).unwrap();
+        schedule.add_block(priority_block((10, 0), (11, 0), "阅读", 0)).unwrap();
+        schedule.add_block(priority_block((12, 0), (13, 0), "午饭", 5)).unwrap();
+
+        let evicted = schedule.add_block_priority(priority_block((9, 30), (10, 30), "紧急会议", 3)).unwrap();
+        let evicted: Vec<&str> = evicted.iter().map(|b| b.name()).collect();
+        assert_eq!(evicted, vec!["整理邮件", "阅读"]);
+        let names: Vec<&str> = schedule.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["紧急会议", "午饭"]);
+
+        // 整体作为一次编辑撤销
+        assert!(schedule.undo());
+        assert_eq!(schedule.len(), 3);
+    }
+
+    #[test]
+    fn test_add_block_priority_rejects_equal() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(priority_block((9, 0), (10, 0), "低", 0)).unwrap();
+        schedule.add_block(priority_block((10, 0), (11, 0), "同级", 3)).unwrap();
+
+        let result = schedule.add_block_priority(priority_block((9, 30), (10, 30), "新", 3));
+        assert!(matches!(result, Err(ScheduleError::Overlap { existing, .. }) if existing.name() == "同级"));
+        // 低优先级的时间段也没有被移除
+        assert_eq!(schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_add_block_priority_no_conflict() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(priority_block((9, 0), (10, 0), "上午", 5)).unwrap();
+        let evicted = schedule.add_block_priority(priority_block((10, 0), (11, 0), "之后", 0)).unwrap();
+        assert!(evicted.is_empty());
+        assert_eq!(schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_clip_to_window() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((7, 0), (7, 30), "早饭")).unwrap();
+        schedule.add_block(block((7, 30), (8, 30), "通勤")).unwrap();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 30), (12, 30), "午饭")).unwrap();
+        schedule.add_block(block((14, 0), (15, 0), "下午")).unwrap();
+
+        let visible = schedule.clip_to_window(t(8, 0), t(12, 0));
+        assert_eq!(
+            visible,
+            vec![
+                block((8, 0), (8, 30), "通勤"),
+                block((9, 0), (10, 0), "会议"),
+                block((11, 30), (12, 0), "午饭"),
+            ]
+        );
+        assert!(schedule.clip_to_window(t(16, 0), t(18, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_placements() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "午饭")).unwrap();
+        let tasks = vec![
+            ("回邮件".to_string(), 30),
+            ("写报告".to_string(), 60),
+            ("复盘".to_string(), 30),
+        ];
+
+        let placements = schedule.suggest_placements(&tasks, t(8, 0), t(13, 0));
+        assert_eq!(
+            placements,
+            vec![Some((t(8, 0), t(8, 30))), Some((t(10, 0), t(11, 0))), Some((t(8, 30), t(9, 0)))]
+        );
+        // 日程本身不变
+        assert_eq!(schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_suggest_placements_too_big() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        let tasks = vec![("读书".to_string(), 30), ("长任务".to_string(), 120), ("散步".to_string(), 60)];
+
+        let placements = schedule.suggest_placements(&tasks, t(8, 0), t(11, 0));
+        assert_eq!(placements, vec![Some((t(8, 0), t(8, 30))), None, Some((t(10, 0), t(11, 0)))]);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut schedule = raw_schedule(vec![
+            block((10, 0), (11, 0), "写代码"),
+            block((13, 0), (14, 0), "午休"),
+            block((9, 0), (10, 0), "写代码"),
+            block((11, 0), (12, 0), "写代码"),
+            block((12, 0), (13, 0), "午饭"),
+        ]);
+        schedule.normalize().unwrap();
+        assert_eq!(
+            schedule.blocks(),
+            [
+                block((9, 0), (12, 0), "写代码"),
+                block((12, 0), (13, 0), "午饭"),
+                block((13, 0), (14, 0), "午休"),
+            ]
+        );
+        // 幂等
+        let once = schedule.blocks().to_vec();
+        schedule.normalize().unwrap();
+        assert_eq!(schedule.blocks(), once);
+        // 整理作为一次编辑撤销
+        assert!(schedule.undo());
+        assert_eq!(schedule.len(), 5);
+    }
+
+    #[test]
+    fn test_normalize_reports_overlap() {
+        let mut schedule = raw_schedule(vec![block((10, 0), (11, 0), "乙"), block((9, 0), (10, 30), "甲")]);
+        let result = schedule.normalize();
+        assert!(matches!(
+            result,
+            Err(ScheduleError::Overlap { existing, incoming }) if existing.name() == "甲" && incoming.name() == "乙"
+        ));
+        // 出错时保持原样
+        assert_eq!(schedule.blocks()[0].name(), "乙");
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((7, 0), (8, 30), "晨跑")).unwrap();
+        schedule.add_block(fixed_block((9, 0), (10, 0), "例会")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "写代码")).unwrap();
+        schedule.add_block(block((14, 0), (15, 0), "评审")).unwrap();
+
+        let stats = schedule.stats(t(8, 0), t(16, 0));
+        assert_eq!(stats.busy_minutes, 30 + 60 + 60 + 60);
+        assert_eq!(stats.free_minutes, 8 * 60 - 210);
+        assert_eq!(stats.block_count, 4);
+        assert_eq!(stats.fixed_count, 1);
+        assert_eq!(stats.largest_gap, Some((t(11, 0), t(14, 0))));
+        assert_eq!(stats.completion_rate, 210.0 / 480.0);
+    }
+
+    #[test]
+    fn test_replace_block() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((12, 0), (13, 0), "午饭")).unwrap();
+
+        let old = schedule.replace_block(0, block((13, 0), (14, 0), "评审")).unwrap();
+        assert_eq!(old, block((9, 0), (10, 0), "会议"));
+        // 开始时间变化后重新排序
+        let names: Vec<&str> = schedule.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["午饭", "评审"]);
+
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[0], block((9, 0), (10, 0), "会议"));
+    }
+
+    #[test]
+    fn test_replace_block_conflict() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((12, 0), (13, 0), "午饭")).unwrap();
+
+        // 与自身原位置重叠没关系
+        schedule.replace_block(0, block((9, 30), (10, 30), "会议")).unwrap();
+        let result = schedule.replace_block(0, block((11, 0), (12, 30), "会议"));
+        assert!(matches!(result, Err(ScheduleError::Overlap { existing, .. }) if existing.name() == "午饭"));
+        assert_eq!(schedule.blocks()[0], block((9, 30), (10, 30), "会议"));
+
+        let result = schedule.replace_block(5, block((15, 0), (16, 0), "越界"));
+        assert!(matches!(result, Err(ScheduleError::IndexOutOfBounds(5))));
+        assert_eq!(schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_occupancy() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 30), (11, 0), "电话")).unwrap();
+        let occupancy = schedule.occupancy();
+
+        let minute = |h: u16, m: u16| h * 60 + m;
+        for m in 0..24 * 60 {
+            let expected = schedule.iter().any(|b| b.contains(TimeOfDay::try_from(m).unwrap()));
+            assert_eq!(occupancy.is_busy(m), expected, "第 {} 分钟", m);
+            assert_eq!(occupancy.is_free(m), !expected, "第 {} 分钟", m);
+        }
+        assert!(occupancy.is_busy(minute(9, 0)));
+        assert!(occupancy.is_free(minute(10, 0)));
+        assert!(occupancy.is_busy(minute(10, 59)));
+        assert!(occupancy.is_free(minute(11, 0)));
+        assert!(!occupancy.is_free(24 * 60));
+    }
+
+    #[test]
+    fn test_rebalance() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((8, 0), (8, 40), "阅读")).unwrap();
+        schedule.add_block(block((8, 40), (9, 20), "写作")).unwrap();
+        schedule.add_block(fixed_block((12, 0), (13, 0), "午饭")).unwrap();
+        schedule.add_block(block((13, 0), (14, 0), "散步")).unwrap();
+
+        schedule.rebalance(t(8, 0), t(17, 0)).unwrap();
+        assert_eq!(
+            schedule.blocks(),
+            [
+                // 8:00-12:00 共 240 分钟，空闲 160 分钟分成 3 份：54、53、53
+                block((8, 54), (9, 34), "阅读"),
+                block((10, 27), (11, 7), "写作"),
+                fixed_block((12, 0), (13, 0), "午饭"),
+                // 13:00-17:00 共 240 分钟，空闲 180 分钟分成 2 份
+                block((14, 30), (15, 30), "散步"),
+            ]
+        );
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[0], block((8, 0), (8, 40), "阅读"));
+    }
+
+    #[test]
+    fn test_rebalance_over_full() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((8, 0), (10, 0), "写作")).unwrap();
+        schedule.add_block(fixed_block((10, 0), (11, 0), "会议")).unwrap();
+
+        let result = schedule.rebalance(t(9, 0), t(12, 0));
+        assert!(matches!(result, Err(ScheduleError::NotEnoughTime { needed: 120, available: 60 })));
+        assert_eq!(schedule.blocks()[0], block((8, 0), (10, 0), "写作"));
+    }
+
+    #[test]
+    fn test_busy_intervals() {
+        let mut schedule = Schedule::new();
+        assert!(schedule.busy_intervals().is_empty());
+
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((14, 0), (15, 0), "评审")).unwrap();
+        assert_eq!(schedule.busy_intervals(), vec![(t(9, 0), t(10, 0)), (t(14, 0), t(15, 0))]);
+
+        schedule.add_block(block((10, 0), (11, 0), "写代码")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "写代码")).unwrap();
+        assert_eq!(schedule.busy_intervals(), vec![(t(9, 0), t(12, 0)), (t(14, 0), t(15, 0))]);
+    }
+
+    #[test]
+    fn test_count_in_window() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((7, 0), (7, 30), "早饭")).unwrap();
+        schedule.add_block(block((7, 30), (8, 30), "通勤")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 30), (13, 0), "午饭")).unwrap();
+        schedule.add_block(block((15, 0), (16, 0), "下午")).unwrap();
+
+        // 通勤跨过左边界，会议完全在内，午饭跨过右边界，早饭在窗口外
+        assert_eq!(schedule.count_in_window(t(8, 0), t(12, 0)), 3);
+        assert_eq!(schedule.count_in_window(t(10, 15), t(10, 45)), 1);
+        // 与午饭、下午首尾相接不算
+        assert_eq!(schedule.count_in_window(t(13, 0), t(15, 0)), 0);
+        assert_eq!(schedule.count_in_window(t(12, 0), t(8, 0)), 0);
+    }
+
+    #[test]
+    fn test_shift_from_cascade() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((8, 0), (9, 0), "早会")).unwrap();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "写代码")).unwrap();
+        schedule.add_block(fixed_block((12, 0), (13, 0), "午饭")).unwrap();
+        schedule.add_block(block((14, 0), (15, 0), "评审")).unwrap();
+
+        schedule.shift_from(1, 30).unwrap();
+        assert_eq!(
+            schedule.blocks(),
+            [
+                block((8, 0), (9, 0), "早会"),
+                block((9, 30), (10, 30), "会议"),
+                block((10, 30), (11, 30), "写代码"),
+                fixed_block((12, 0), (13, 0), "午饭"),
+                block((14, 30), (15, 30), "评审"),
+            ]
+        );
+        // 一次撤销恢复全部
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[1], block((9, 0), (10, 0), "会议"));
+        assert_eq!(schedule.blocks()[4], block((14, 0), (15, 0), "评审"));
+    }
+
+    #[test]
+    fn test_shift_from_blocked_by_fixed() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((10, 0), (11, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (11, 45), "写代码")).unwrap();
+        schedule.add_block(fixed_block((12, 0), (13, 0), "午饭")).unwrap();
+        let before = schedule.blocks().to_vec();
+
+        let result = schedule.shift_from(0, 30);
+        assert!(matches!(
+            result,
+            Err(ScheduleError::Overlap { existing, incoming }) if existing.name() == "午饭" && incoming.name() == "写代码"
+        ));
+        assert_eq!(schedule.blocks(), before);
+
+        assert!(matches!(schedule.shift_from(0, 14 * 60), Err(ScheduleError::OutOfDay)));
+        assert!(matches!(schedule.shift_from(3, 10), Err(ScheduleError::IndexOutOfBounds(3))));
+        assert_eq!(schedule.blocks(), before);
+    }
+
+    #[test]
+    fn test_shift_from_cannot_jump_over_fixed() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((10, 0), (11, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (11, 45), "写代码")).unwrap();
+        schedule.add_block(fixed_block((12, 0), (13, 0), "午饭")).unwrap();
+        let before = schedule.blocks().to_vec();
+
+        // 平移后会议和写代码都落在午饭之后，不与之重叠，但越过了它
+        let result = schedule.shift_from(0, 4 * 60);
+        assert!(matches!(
+            result,
+            Err(ScheduleError::Overlap { existing, incoming }) if existing.name() == "午饭" && incoming.name() == "会议"
+        ));
+        assert_eq!(schedule.blocks(), before);
+    }
+
+    #[test]
+    fn test_add_block_nudged() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 30), (11, 0), "评审")).unwrap();
+
+        // 不冲突时原样插入
+        let added = schedule.add_block_nudged(block((8, 0), (8, 30), "早读"), 15).unwrap();
+        assert_eq!(added, &block((8, 0), (8, 30), "早读"));
+
+        // 向后挪到会议结束
+        let added = schedule.add_block_nudged(block((9, 45), (10, 15), "电话"), 20).unwrap();
+        assert_eq!(added, &block((10, 0), (10, 30), "电话"));
+        assert_eq!(schedule.len(), 4);
+        assert_eq!(schedule.blocks()[2].name(), "电话");
+    }
+
+    #[test]
+    fn test_add_block_nudged_exhausts_budget() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 30), (11, 0), "评审")).unwrap();
+        schedule.add_block(block((23, 0), (23, 50), "夜读")).unwrap();
+
+        let result = schedule.add_block_nudged(block((9, 30), (10, 15), "电话"), 60);
+        assert!(matches!(
+            result,
+            Err(ScheduleError::Overlap { existing, incoming }) if existing.name() == "会议" && incoming.start_time() == t(9, 30)
+        ));
+        // 挪到一天结束也找不到空位
+        let result = schedule.add_block_nudged(block((23, 30), (23, 45), "冥想"), 30);
+        assert!(matches!(result, Err(ScheduleError::Overlap { .. })));
+        assert_eq!(schedule.len(), 3);
+    }
+
+    #[test]
+    fn test_earliest_start_and_latest_end() {
+        let mut schedule = Schedule::new();
+        assert_eq!(schedule.earliest_start(), None);
+        assert_eq!(schedule.latest_end(), None);
+
+        schedule.add_block(block((13, 0), (17, 30), "写代码")).unwrap();
+        schedule.add_block(block((8, 30), (9, 0), "早会")).unwrap();
+        schedule.add_block(block((9, 0), (10, 0), "评审")).unwrap();
+        assert_eq!(schedule.earliest_start(), Some(t(8, 30)));
+        assert_eq!(schedule.latest_end(), Some(t(17, 30)));
+    }
+
+    #[test]
+    fn test_assign_colors_by_tag() {
+        let tagged = |start: (u16, u16), end: (u16, u16), name: &str, tags: &[&str]| {
+            let mut block = block(start, end, name);
+            block.set_tags(tags.iter().map(|t| t.to_string()).collect()).unwrap();
+            block
+        };
+        let mut schedule = Schedule::new();
+        schedule.add_block(tagged((9, 0), (10, 0), "写代码", &["工作", "专注"])).unwrap();
+        schedule.add_block(tagged((10, 0), (11, 0), "评审", &["工作"])).unwrap();
+        schedule.add_block(tagged((12, 0), (13, 0), "跑步", &["运动"])).unwrap();
+        let mut untagged = block((14, 0), (15, 0), "午休");
+        untagged.set_color(Some("#cccccc".to_string()));
+        schedule.add_block(untagged).unwrap();
+
+        let palette = ["#5b7db1", "#6aa86f", "#d98c5f", "#b46aa8", "#c9b458"];
+        schedule.assign_colors_by_tag(&palette);
+        let colors: Vec<Option<&str>> = schedule.iter().map(Block::color).collect();
+        assert_eq!(colors[0], colors[1]);
+        assert!(colors[..3].iter().all(|c| c.is_some_and(|c| palette.contains(&c))));
+        assert_eq!(colors[3], Some("#cccccc"));
+
+        // 结果稳定：重复调用没有变化，不产生新的撤销记录
+        schedule.assign_colors_by_tag(&palette);
+        assert!(schedule.undo());
+        assert!(schedule.blocks()[0].color().is_none());
+    }
+
+    #[test]
+    fn test_enforce_buffer() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((8, 0), (9, 0), "写代码")).unwrap();
+        schedule.add_block(fixed_block((9, 15), (10, 0), "会议")).unwrap();
+        schedule.add_block(fixed_block((10, 0), (11, 0), "面试")).unwrap();
+        schedule.add_block(block((11, 30), (12, 0), "回邮件")).unwrap();
+        assert!(schedule.enforce_buffer(15).is_ok());
+        assert!(schedule.enforce_buffer(0).is_ok());
+    }
+
+    #[test]
+    fn test_enforce_buffer_violation() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((8, 0), (9, 0), "写代码")).unwrap();
+        schedule.add_block(fixed_block((9, 15), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 5), (11, 0), "回邮件")).unwrap();
+
+        match schedule.enforce_buffer(20) {
+            Err(ScheduleError::BufferTooSmall { fixed, block, gap, required }) => {
+                assert_eq!(fixed.name(), "会议");
+                assert_eq!(block.name(), "写代码");
+                assert_eq!((gap, required), (15, 20));
+            }
+            other => panic!("应返回缓冲不足错误：{:?}", other),
+        }
+        match schedule.enforce_buffer(10) {
+            Err(ScheduleError::BufferTooSmall { block, gap, .. }) => {
+                assert_eq!(block.name(), "回邮件");
+                assert_eq!(gap, 5);
+            }
+            other => panic!("应返回缓冲不足错误：{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_move_to_gap() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 30), (11, 0), "回邮件")).unwrap();
+        schedule.add_block(block((13, 0), (14, 0), "写代码")).unwrap();
+        let gaps = schedule.free_gaps(t(8, 0), t(18, 0));
+        assert_eq!(gaps[2], (t(11, 0), t(13, 0)));
+
+        // 回邮件移到 11:00-13:00 的空闲时间
+        schedule.move_to_gap(1, 2, t(8, 0), t(18, 0)).unwrap();
+        assert_eq!(schedule.blocks()[1], block((11, 0), (11, 30), "回邮件"));
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[1], block((10, 30), (11, 0), "回邮件"));
+    }
+
+    #[test]
+    fn test_move_to_gap_rejected() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(fixed_block((8, 30), (9, 0), "站会")).unwrap();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 30), (11, 0), "回邮件")).unwrap();
+        let before = schedule.blocks().to_vec();
+
+        // 10:00-10:30 放不下一小时的会议
+        let result = schedule.move_to_gap(1, 1, t(8, 0), t(18, 0));
+        assert!(matches!(result, Err(ScheduleError::NotEnoughTime { needed: 60, available: 30 })));
+        // 固定时间段不能移动
+        let result = schedule.move_to_gap(0, 2, t(8, 0), t(18, 0));
+        assert!(matches!(result, Err(ScheduleError::Block(BlockError::FixedBlockTimeChange))));
+        let result = schedule.move_to_gap(1, 9, t(8, 0), t(18, 0));
+        assert!(matches!(result, Err(ScheduleError::IndexOutOfBounds(9))));
+        assert_eq!(schedule.blocks(), before);
+    }
+
+    #[test]
+    fn test_search_ranking() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((8, 0), (9, 0), "Team Review")).unwrap();
+        schedule.add_block(block((9, 0), (10, 0), "Code review")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "Review")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "Reviewer sync")).unwrap();
+        schedule.add_block(block((13, 0), (14, 0), "Read the view")).unwrap();
+        schedule.add_block(block((14, 0), (15, 0), "午饭")).unwrap();
+
+        let results = schedule.search("REVIEW");
+        let indices: Vec<usize> = results.iter().map(|&(i, _)| i).collect();
+        // 完全相同 > 前缀 > 包含（按时间顺序）> 模糊
+        assert_eq!(indices, vec![2, 3, 0, 1, 4]);
+        assert_eq!(results[0].1, 1.0);
+        assert!(results[1].1 > results[2].1);
+        assert!(results[4].1 < results[3].1);
+    }
+
+    #[test]
+    fn test_search_excludes_non_matches() {
+        let schedule = Schedule::from_text("09:00-10:00 晨会\n12:00-13:00 午饭\n").unwrap();
+        assert_eq!(schedule.search("午"), vec![(1, 0.75)]);
+        assert!(schedule.search("晚饭").is_empty());
+        assert!(schedule.search("  ").is_empty());
+    }
+
+    #[test]
+    fn test_free_busy_half_booked() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 40), (11, 30), "评审")).unwrap();
+        schedule.add_block(block((11, 45), (12, 0), "电话")).unwrap();
+
+        // 09:00-13:00 按 30 分钟切成 8 槽，一半忙碌
+        let slots = schedule.free_busy(30, t(9, 0), t(13, 0));
+        assert_eq!(slots, vec![true, true, false, true, true, false, false, false]);
+        // 11:30-12:00 只占一半，不算忙碌
+        assert!(!slots[5]);
+    }
+
+    #[test]
+    fn test_free_busy_partial_last_slot() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 40), (10, 0), "电话")).unwrap();
+        // 09:00-10:00 按 45 分钟切：09:00-09:45 与不足一槽的 09:45-10:00
+        assert_eq!(schedule.free_busy(45, t(9, 0), t(10, 0)), vec![false, true]);
+        assert!(schedule.free_busy(0, t(9, 0), t(10, 0)).is_empty());
+        assert!(schedule.free_busy(30, t(10, 0), t(9, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_activity_at_each() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (9, 45), "会议")).unwrap();
+        schedule.add_block(block((10, 0), (10, 30), "评审")).unwrap();
+
+        let activity = schedule.activity_at_each(15, t(8, 45), t(10, 30));
+        let label = |name: &str| Some(name.to_string());
+        assert_eq!(
+            activity,
+            vec![
+                (t(8, 45), None),
+                (t(9, 0), label("会议")),
+                (t(9, 15), label("会议")),
+                (t(9, 30), label("会议")),
+                // 09:45 是会议的结束时间，已经空闲
+                (t(9, 45), None),
+                (t(10, 0), label("评审")),
+                (t(10, 15), label("评审")),
+            ]
+        );
+        assert!(schedule.activity_at_each(0, t(9, 0), t(10, 0)).is_empty());
+        assert!(schedule.activity_at_each(15, t(10, 0), t(9, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_group_by_hour() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((14, 30), (16, 0), "写代码")).unwrap();
+        schedule.add_block(block((9, 0), (9, 20), "晨会")).unwrap();
+        schedule.add_block(block((9, 40), (10, 30), "回邮件")).unwrap();
+        schedule.add_block(block((10, 30), (11, 0), "电话")).unwrap();
+
+        let groups = schedule.group_by_hour();
+        assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec![9, 10, 14]);
+        let names = |hour: u16| groups[&hour].iter().map(|b| b.name()).collect::<Vec<_>>();
+        assert_eq!(names(9), vec!["晨会", "回邮件"]);
+        // 跨越整点的时间段只列在开始的小时
+        assert_eq!(names(10), vec!["电话"]);
+        assert_eq!(names(14), vec!["写代码"]);
+        assert!(!groups.contains_key(&15));
+        assert!(Schedule::new().group_by_hour().is_empty());
+    }
+
+    #[test]
+    fn test_transaction_rolls_back() {
+        use std::sync::{Arc, Mutex};
+
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((10, 0), (11, 0), "评审")).unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        schedule.on_change(Box::new(move |event| sink.lock().unwrap().push(event.clone())));
+
+        let result = schedule.transaction(|s| {
+            s.add_block(block((9, 0), (10, 0), "会议"))?;
+            s.add_block(block((10, 30), (11, 30), "冲突"))
+        });
+        assert!(matches!(result, Err(ScheduleError::Overlap { .. })));
+        assert_eq!(schedule.blocks(), [block((10, 0), (11, 0), "评审")]);
+        assert!(schedule.undo());
+        assert!(schedule.is_empty());
+        // 先收到添加“会议”，回滚时再收到删除
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ScheduleEvent::Added(block((9, 0), (10, 0), "会议")),
+                ScheduleEvent::Removed(block((9, 0), (10, 0), "会议")),
+                ScheduleEvent::Removed(block((10, 0), (11, 0), "评审")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transaction_commits_as_one_edit() {
+        let mut schedule = Schedule::new();
+        schedule
+            .transaction(|s| {
+                s.add_block(block((9, 0), (10, 0), "会议"))?;
+                s.add_block(block((10, 0), (11, 0), "评审"))
+            })
+            .unwrap();
+        assert_eq!(schedule.len(), 2);
+        assert!(schedule.undo());
+        assert!(schedule.is_empty());
+        assert!(!schedule.can_undo());
+        assert!(schedule.redo());
+        assert_eq!(schedule.len(), 2);
+    }
+
+    #[test]
+    fn test_free_before() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "晨会")).unwrap();
+        schedule.add_block(block((11, 0), (14, 40), "写代码")).unwrap();
+        schedule.add_block(block((15, 0), (16, 0), "会议")).unwrap();
+
+        // 14:40-15:00 只有 20 分钟，往前找到 10:00-11:00
+        assert_eq!(schedule.free_before(t(15, 0), 30, t(8, 0)), Some((t(10, 0), t(11, 0))));
+        assert_eq!(schedule.free_before(t(15, 0), 20, t(8, 0)), Some((t(14, 40), t(15, 0))));
+        // 截止时间落在空闲时间段中间时截断
+        assert_eq!(schedule.free_before(t(10, 45), 30, t(8, 0)), Some((t(10, 0), t(10, 45))));
+    }
+
+    #[test]
+    fn test_free_before_no_slot() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((8, 20), (9, 0), "早饭")).unwrap();
+        schedule.add_block(block((9, 15), (15, 0), "上课")).unwrap();
+        assert_eq!(schedule.free_before(t(15, 0), 30, t(8, 0)), None);
+        assert_eq!(schedule.free_before(t(8, 0), 10, t(8, 0)), None);
+    }
+
+    #[test]
+    fn test_find_by_id() {
+        let mut schedule = Schedule::new();
+        let mut review = block((10, 0), (11, 0), "评审");
+        review.set_id(Some("JIRA-7".to_string()));
+        assert_eq!(review.id(), Some("JIRA-7"));
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(review).unwrap();
+
+        assert_eq!(schedule.find_by_id("JIRA-7").map(Block::name), Some("评审"));
+        assert!(schedule.find_by_id("JIRA-8").is_none());
+
+        // 标识不要求唯一，返回时间最早的
+        let mut duplicate = block((8, 0), (8, 30), "早读");
+        duplicate.set_id(Some("JIRA-7".to_string()));
+        schedule.add_block(duplicate).unwrap();
+        assert_eq!(schedule.find_by_id("JIRA-7").map(Block::name), Some("早读"));
+    }
+
+    #[test]
+    fn test_iter_gaps_lazy() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((11, 0), (12, 0), "评审")).unwrap();
+
+        let mut gaps = schedule.iter_gaps(t(8, 0), t(18, 0));
+        assert_eq!(gaps.next(), Some((t(8, 0), t(9, 0))));
+        assert_eq!(gaps.next(), Some((t(10, 0), t(11, 0))));
+        assert_eq!(gaps.next(), Some((t(12, 0), t(18, 0))));
+        assert_eq!(gaps.next(), None);
+        assert_eq!(gaps.next(), None);
+
+        // 短路：只取第一个足够长的空闲时间段
+        let long = schedule
+            .iter_gaps(t(8, 0), t(18, 0))
+            .find(|(start, end)| end.duration_since(*start).is_some_and(|d| d.minutes() >= 120));
+        assert_eq!(long, Some((t(12, 0), t(18, 0))));
+        assert_eq!(schedule.iter_gaps(t(9, 0), t(10, 0)).next(), None);
+    }
+
+    #[test]
+    fn test_partition_fixed_mixed() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(fixed_block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 0), (11, 0), "写代码")).unwrap();
+        schedule.add_block(block((13, 0), (14, 0), "回邮件")).unwrap();
+        schedule.add_block(fixed_block((15, 0), (16, 0), "面试")).unwrap();
+
+        let (fixed, flexible) = schedule.partition_fixed();
+        assert_eq!(fixed.iter().map(|b| b.name()).collect::<Vec<_>>(), vec!["会议", "面试"]);
+        assert_eq!(flexible.iter().map(|b| b.name()).collect::<Vec<_>>(), vec!["写代码", "回邮件"]);
+        assert_eq!(schedule.percent_fixed(), 0.5);
+    }
+
+    #[test]
+    fn test_partition_fixed_uniform() {
+        let mut all_fixed = Schedule::new();
+        all_fixed.add_block(fixed_block((9, 0), (10, 0), "会议")).unwrap();
+        all_fixed.add_block(fixed_block((10, 0), (11, 0), "面试")).unwrap();
+        assert_eq!(all_fixed.partition_fixed().1.len(), 0);
+        assert_eq!(all_fixed.percent_fixed(), 1.0);
+
+        let mut all_flexible = Schedule::new();
+        all_flexible.add_block(block((9, 0), (10, 0), "写代码")).unwrap();
+        assert_eq!(all_flexible.partition_fixed().0.len(), 0);
+        assert_eq!(all_flexible.percent_fixed(), 0.0);
+        assert_eq!(Schedule::new().percent_fixed(), 0.0);
+    }
+
+    #[test]
+    fn test_retain_fixed_only() {
+        let mut schedule = Schedule::new();
+        schedule.add_block(fixed_block((9, 0), (10, 0), "会议")).unwrap();
+        schedule.add_block(block((10, 0), (10, 15), "休息")).unwrap();
+        schedule.add_block(block((10, 15), (12, 0), "写代码")).unwrap();
+        schedule.add_block(fixed_block((14, 0), (15, 0), "面试")).unwrap();
+
+        let mut visited = 0;
+        schedule.retain(|b| {
+            visited += 1;
+            b.is_fixed()
+        });
+        assert_eq!(visited, 4);
+        assert_eq!(schedule.iter().map(Block::name).collect::<Vec<_>>(), vec!["会议", "面试"]);
+        // 一次撤销恢复全部
+        assert!(schedule.undo());
+        assert_eq!(schedule.len(), 4);
+    }
+
+    #[test]
+    fn test_resolve_overlaps_by_clipping() {
+        let mut schedule = raw_schedule(vec![
+            block((10, 30), (12, 0), "写代码"),
+            block((9, 0), (11, 0), "会议"),
+            block((13, 0), (14, 0), "评审"),
+        ]);
+        schedule.resolve_overlaps_by_clipping().unwrap();
+        assert_eq!(
+            schedule.blocks(),
+            [
+                block((9, 0), (11, 0), "会议"),
+                block((11, 0), (12, 0), "写代码"),
+                block((13, 0), (14, 0), "评审"),
+            ]
+        );
+        assert!(Schedule::try_from_blocks(schedule.blocks().to_vec()).is_ok());
+        assert!(schedule.undo());
+        assert_eq!(schedule.blocks()[0].name(), "写代码");
+    }
+
+    #[test]
+    fn test_resolve_overlaps_drops_covered_block() {
+        let mut schedule = raw_schedule(vec![
+            block((9, 0), (12, 0), "培训"),
+            block((10, 0), (11, 0), "电话"),
+            block((11, 30), (12, 30), "午饭"),
+        ]);
+        schedule.resolve_overlaps_by_clipping().unwrap();
+        assert_eq!(
+            schedule.blocks(),
+            [block((9, 0), (12, 0), "培训"), block((12, 0), (12, 30), "午饭")]
+        );
+    }
+
+    #[test]
+    fn test_resolve_overlaps_keeps_fixed_and_locked() {
+        let blocks = vec![block((9, 0), (11, 0), "写代码"), fixed_block((10, 0), (11, 30), "周会")];
+        let mut schedule = raw_schedule(blocks.clone());
+        let result = schedule.resolve_overlaps_by_clipping();
+        assert!(matches!(result, Err(ScheduleError::Block(BlockError::FixedBlockTimeChange))));
+        assert_eq!(schedule.blocks(), blocks);
+        assert!(!schedule.can_undo());
+
+        let imported = block((10, 0), (11, 30), "外部日历").to_builder().locked(true).build().unwrap();
+        let blocks = vec![block((9, 0), (11, 0), "写代码"), imported];
+        let mut schedule = raw_schedule(blocks.clone());
+        let result = schedule.resolve_overlaps_by_clipping();
+        assert!(matches!(result, Err(ScheduleError::Block(BlockError::Locked))));
+        assert_eq!(schedule.blocks(), blocks);
+    }
+
+    #[test]
+    fn test_resolve_overlaps_respects_min_duration() {
+        let blocks = vec![block((9, 0), (11, 0), "会议"), block((10, 0), (11, 10), "电话")];
+        let mut schedule = raw_schedule(blocks.clone()).with_min_duration(15);
+        let result = schedule.resolve_overlaps_by_clipping();
+        assert!(matches!(result, Err(ScheduleError::TooShort { min: 15, actual: 10 })));
+        assert_eq!(schedule.blocks(), blocks);
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+
+use crate::data::timeofday::TimeOfDay;
+
+/// 以分钟为单位的时长
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Duration(u16);
+
+impl Duration {
+    /// 由分钟数创建时长
+    pub fn from_minutes(minutes: u16) -> Duration {
+        Duration(minutes)
+    }
+    /// 获取总分钟数
+    pub fn minutes(&self) -> u16 {
+        self.0
+    }
+    /// 获取总分钟数（u32，便于与累计统计等旧接口互通）
+    pub fn as_minutes(&self) -> u32 {
+        u32::from(self.0)
+    }
+    /// 拆分为 (小时, 分钟)
+    pub fn hours_minutes(&self) -> (u16, u16) {
+        (self.0 / 60, self.0 % 60)
+    }
+    /// 相加，超出 u16 范围时返回 None
+    pub fn checked_add(self, other: Duration) -> Option<Duration> {
+        self.0.checked_add(other.0).map(Duration)
+    }
+}
+
+impl fmt::Display for Duration {
+    /// 显示格式："1h 30m"、"2h"、"45m"，零时长为 "0m"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.hours_minutes() {
+            (0, minutes) => write!(f, "{}m", minutes),
+            (hours, 0) => write!(f, "{}h", hours),
+            (hours, minutes) => write!(f, "{}h {}m", hours, minutes),
+        }
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    /// 相加，超出 u16 范围时取最大值（需要区分溢出时用 `checked_add`）
+    fn add(self, other: Duration) -> Duration {
+        Duration(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sum for Duration {
+    fn sum<I: Iterator<Item = Duration>>(iter: I) -> Duration {
+        iter.fold(Duration::default(), Add::add)
+    }
+}
+
+impl TimeOfDay {
+    /// 加上时长，超出一天时返回 None
+    pub fn checked_add(self, duration: Duration) -> Option<TimeOfDay> {
+        u16::from(self)
+            .checked_add(duration.minutes())
+            .and_then(|minutes| TimeOfDay::try_from(minutes).ok())
+    }
+    /// 减去时长，早于午夜时返回 None
+    pub fn checked_sub(self, duration: Duration) -> Option<TimeOfDay> {
+        u16::from(self)
+            .checked_sub(duration.minutes())
+            .and_then(|minutes| TimeOfDay::try_from(minutes).ok())
+    }
+    /// 距离更早的时间 `earlier` 过去了多久，`earlier` 晚于自身时返回 None
+    pub fn duration_since(self, earlier: TimeOfDay) -> Option<Duration> {
+        u16::from(self).checked_sub(u16::from(earlier)).map(Duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_construction() {
+        let duration = Duration::from_minutes(90);
+        assert_eq!(duration.minutes(), 90);
+        assert_eq!(duration.as_minutes(), 90);
+        assert_eq!(duration.hours_minutes(), (1, 30));
+        assert_eq!(Duration::default().minutes(), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Duration::from_minutes(90).to_string(), "1h 30m");
+        assert_eq!(Duration::from_minutes(120).to_string(), "2h");
+        assert_eq!(Duration::from_minutes(45).to_string(), "45m");
+        assert_eq!(Duration::from_minutes(0).to_string(), "0m");
+    }
+
+    #[test]
+    fn test_sum() {
+        let total: Duration = [30, 45, 15].into_iter().map(Duration::from_minutes).sum();
+        assert_eq!(total, Duration::from_minutes(90));
+    }
+
+    #[test]
+    fn test_add_overflow() {
+        let max = Duration::from_minutes(u16::MAX);
+        let hour = Duration::from_minutes(60);
+        assert_eq!(max + hour, max);
+        assert_eq!(max.checked_add(hour), None);
+        assert_eq!(hour.checked_add(hour), Some(Duration::from_minutes(120)));
+        let total: Duration = [max, hour, hour].into_iter().sum();
+        assert_eq!(total, max);
+    }
+
+    #[test]
+    fn test_time_of_day_arithmetic() {
+        let nine = TimeOfDay::new(9, 0).unwrap();
+        let ninety = Duration::from_minutes(90);
+        assert_eq!(nine.checked_add(ninety), TimeOfDay::new(10, 30));
+        assert_eq!(nine.checked_sub(ninety), TimeOfDay::new(7, 30));
+        assert_eq!(TimeOfDay::new(23, 0).unwrap().checked_add(ninety), None);
+        assert_eq!(TimeOfDay::new(1, 0).unwrap().checked_sub(ninety), None);
+
+        let later = TimeOfDay::new(10, 30).unwrap();
+        assert_eq!(later.duration_since(nine), Some(ninety));
+        assert_eq!(nine.duration_since(later), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! 日程导出为其他文本格式
+
+use crate::data::day::Schedule;
+
+impl Schedule {
+    /// 导出为 Markdown 表格，每个时间段一行，按时间顺序排列
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::from("| 时间 | 名称 | 描述 |\n| --- | --- | --- |\n");
+        for block in self {
+            output.push_str(&format!(
+                "| {}-{} | {} | {} |\n",
+                block.start_time(),
+                block.end_time(),
+                escape_markdown_cell(block.name()),
+                escape_markdown_cell(block.description().unwrap_or(""))
+            ));
+        }
+        output
+    }
+    /// 生成可粘贴到 Google 日历“快速添加”的文本，每个时间段一行，`date` 为 (年, 月, 日)
+    ///
+    /// 例如 `会议 on 2024-06-01 from 9:00am to 10:30am`。
+    pub fn quick_add_lines(&self, date: (u16, u8, u8)) -> Vec<String> {
+        let (year, month, day) = date;
+        self.iter()
+            .map(|block| {
+                format!(
+                    "{} on {:04}-{:02}-{:02} from {} to {}",
+                    block.name(),
+                    year,
+                    month,
+                    day,
+                    block.start_time().to_12h(),
+                    block.end_time().to_12h()
+                )
+            })
+            .collect()
+    }
+}
+
+/// 转义表格单元格中的竖线，并把换行替换为空格
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::block::Block;
+    use crate::data::timeofday::TimeOfDay;
+
+    #[test]
+    fn test_to_markdown() {
+        let mut schedule = Schedule::new();
+        schedule
+            .add_block(
+                Block::builder()
+                    .start_time(TimeOfDay::new(14, 0).unwrap())
+                    .end_time(TimeOfDay::new(15, 0).unwrap())
+                    .name("评审 | 设计".to_string())
+                    .is_fixed(false)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        schedule
+            .add_block(
+                Block::builder()
+                    .start_time(TimeOfDay::new(9, 0).unwrap())
+                    .end_time(TimeOfDay::new(10, 30).unwrap())
+                    .name("会议".to_string())
+                    .description(Some("项目讨论".to_string()))
+                    .is_fixed(false)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let markdown = schedule.to_markdown();
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines[0], "| 时间 | 名称 | 描述 |");
+        assert_eq!(lines[2], "| 09:00-10:30 | 会议 | 项目讨论 |");
+        assert_eq!(lines[3], "| 14:00-15:00 | 评审 \\| 设计 |  |");
+    }
+
+    #[test]
+    fn test_quick_add_lines() {
+        let schedule = Schedule::from_text("09:00-10:30 会议\n13:00-14:15 评审\n").unwrap();
+        assert_eq!(
+            schedule.quick_add_lines((2024, 6, 1)),
+            vec![
+                "会议 on 2024-06-01 from 9:00am to 10:30am",
+                "评审 on 2024-06-01 from 1:00pm to 2:15pm",
+            ]
+        );
+    }
+}
@@ -0,0 +1,856 @@
+//! iCalendar (RFC 5545) 导入与导出
+//!
+//! `TimeOfDay` 本身不含日期和时区，导出时由调用方提供日期和可选的 IANA 时区（同时输出
+//! 该时区的 VTIMEZONE）；
+//! 导入时只取指定日期的事件，时间按文件中的字面值读取，不做时区换算；以 `Z` 结尾的 UTC
+//! 时间无法对应到本地时间，这样的事件会被跳过。
+//!
+//! 每个事件的 UID 由日期和时间段的名称、起止时间生成，重复导出同一时间段得到相同的 UID，
+//! 日历应用据此更新已有事件而不是重复添加。
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+
+use crate::data::block::Block;
+use crate::data::day::{Schedule, ScheduleError};
+use crate::data::timeofday::TimeOfDay;
+use crate::utils;
+
+/// 支持的时区及其规则，导出时据此生成 VTIMEZONE
+///
+/// 规则按各地现行的夏令时制度给出，不包含历史上的变更。
+const TIME_ZONES: &[TimeZone] = &[
+    TimeZone::fixed("UTC", "UTC", "+0000"),
+    TimeZone::fixed("Asia/Shanghai", "CST", "+0800"),
+    TimeZone::fixed("Asia/Hong_Kong", "HKT", "+0800"),
+    TimeZone::fixed("Asia/Taipei", "CST", "+0800"),
+    TimeZone::fixed("Asia/Singapore", "+08", "+0800"),
+    TimeZone::fixed("Asia/Tokyo", "JST", "+0900"),
+    TimeZone::fixed("Asia/Seoul", "KST", "+0900"),
+    TimeZone::fixed("Asia/Kolkata", "IST", "+0530"),
+    TimeZone::fixed("Asia/Dubai", "+04", "+0400"),
+    TimeZone::with_daylight("Europe/London", ("GMT", "+0000"), ("BST", "+0100"), EU_DAYLIGHT_LONDON),
+    TimeZone::with_daylight("Europe/Paris", ("CET", "+0100"), ("CEST", "+0200"), EU_DAYLIGHT),
+    TimeZone::with_daylight("Europe/Berlin", ("CET", "+0100"), ("CEST", "+0200"), EU_DAYLIGHT),
+    TimeZone::fixed("Europe/Moscow", "MSK", "+0300"),
+    TimeZone::with_daylight("America/New_York", ("EST", "-0500"), ("EDT", "-0400"), US_DAYLIGHT),
+    TimeZone::with_daylight("America/Chicago", ("CST", "-0600"), ("CDT", "-0500"), US_DAYLIGHT),
+    TimeZone::with_daylight("America/Denver", ("MST", "-0700"), ("MDT", "-0600"), US_DAYLIGHT),
+    TimeZone::with_daylight("America/Los_Angeles", ("PST", "-0800"), ("PDT", "-0700"), US_DAYLIGHT),
+    TimeZone::fixed("America/Sao_Paulo", "-03", "-0300"),
+    TimeZone::with_daylight("Australia/Sydney", ("AEST", "+1000"), ("AEDT", "+1100"), AU_DAYLIGHT),
+    TimeZone::with_daylight("Pacific/Auckland", ("NZST", "+1200"), ("NZDT", "+1300"), NZ_DAYLIGHT),
+];
+
+/// 英国：3 月最后一个周日 01:00 开始，10 月最后一个周日 02:00 结束（当地时间）
+const EU_DAYLIGHT_LONDON: DaylightRule = DaylightRule {
+    start: ("19700329T010000", "FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU"),
+    end: ("19701025T020000", "FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU"),
+};
+/// 中欧：3 月最后一个周日 02:00 开始，10 月最后一个周日 03:00 结束
+const EU_DAYLIGHT: DaylightRule = DaylightRule {
+    start: ("19700329T020000", "FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU"),
+    end: ("19701025T030000", "FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU"),
+};
+/// 美国：3 月第二个周日 02:00 开始，11 月第一个周日 02:00 结束
+const US_DAYLIGHT: DaylightRule = DaylightRule {
+    start: ("19700308T020000", "FREQ=YEARLY;BYMONTH=3;BYDAY=2SU"),
+    end: ("19701101T020000", "FREQ=YEARLY;BYMONTH=11;BYDAY=1SU"),
+};
+/// 澳大利亚东部：10 月第一个周日 02:00 开始，4 月第一个周日 03:00 结束
+const AU_DAYLIGHT: DaylightRule = DaylightRule {
+    start: ("19701004T020000", "FREQ=YEARLY;BYMONTH=10;BYDAY=1SU"),
+    end: ("19700405T030000", "FREQ=YEARLY;BYMONTH=4;BYDAY=1SU"),
+};
+/// 新西兰：9 月最后一个周日 02:00 开始，4 月第一个周日 03:00 结束
+const NZ_DAYLIGHT: DaylightRule = DaylightRule {
+    start: ("19700927T020000", "FREQ=YEARLY;BYMONTH=9;BYDAY=-1SU"),
+    end: ("19700405T030000", "FREQ=YEARLY;BYMONTH=4;BYDAY=1SU"),
+};
+
+/// 一个 IANA 时区：标准时间，以及可选的夏令时
+struct TimeZone {
+    /// IANA 名称，用作 TZID
+    name: &'static str,
+    /// 标准时间的 (缩写, UTC 偏移)
+    standard: (&'static str, &'static str),
+    /// 夏令时的 (缩写, UTC 偏移, 切换规则)
+    daylight: Option<(&'static str, &'static str, DaylightRule)>,
+}
+
+/// 夏令时的切换规则：(首次切换的当地时间, RRULE)
+#[derive(Copy, Clone)]
+struct DaylightRule {
+    /// 进入夏令时
+    start: (&'static str, &'static str),
+    /// 回到标准时间
+    end: (&'static str, &'static str),
+}
+
+impl TimeZone {
+    /// 没有夏令时的时区
+    const fn fixed(name: &'static str, abbreviation: &'static str, offset: &'static str) -> TimeZone {
+        TimeZone {
+            name,
+            standard: (abbreviation, offset),
+            daylight: None,
+        }
+    }
+    /// 有夏令时的时区
+    const fn with_daylight(
+        name: &'static str,
+        standard: (&'static str, &'static str),
+        daylight: (&'static str, &'static str),
+        rule: DaylightRule,
+    ) -> TimeZone {
+        TimeZone {
+            name,
+            standard,
+            daylight: Some((daylight.0, daylight.1, rule)),
+        }
+    }
+    /// 生成 VTIMEZONE 组件的各行
+    fn vtimezone(&self) -> Vec<String> {
+        let (std_name, std_offset) = self.standard;
+        let mut lines = vec!["BEGIN:VTIMEZONE".to_string(), format!("TZID:{}", self.name)];
+        let mut observance = |kind: &str, (dtstart, rrule): (&str, Option<&str>), from: &str, to: &str, name: &str| {
+            lines.push(format!("BEGIN:{}", kind));
+            lines.push(format!("DTSTART:{}", dtstart));
+            if let Some(rrule) = rrule {
+                lines.push(format!("RRULE:{}", rrule));
+            }
+            lines.push(format!("TZOFFSETFROM:{}", from));
+            lines.push(format!("TZOFFSETTO:{}", to));
+            lines.push(format!("TZNAME:{}", name));
+            lines.push(format!("END:{}", kind));
+        };
+        match self.daylight {
+            None => observance("STANDARD", ("19700101T000000", None), std_offset, std_offset, std_name),
+            Some((dst_name, dst_offset, rule)) => {
+                observance("DAYLIGHT", (rule.start.0, Some(rule.start.1)), std_offset, dst_offset, dst_name);
+                observance("STANDARD", (rule.end.0, Some(rule.end.1)), dst_offset, std_offset, std_name);
+            }
+        }
+        lines.push("END:VTIMEZONE".to_string());
+        lines
+    }
+}
+
+/// 内容行的最大长度（字节，不含换行），超出时折行
+const MAX_LINE_OCTETS: usize = 75;
+
+impl Schedule {
+    /// 导出为 iCalendar 文本，`date` 为 (年, 月, 日)
+    ///
+    /// `tz` 为 None 时输出不带时区的浮动时间；否则输出带 TZID 的 DTSTART/DTEND，
+    /// 未知时区返回错误。
+    pub fn to_ics(&self, date: (u16, u8, u8), tz: Option<&str>) -> Result<String, ScheduleError> {
+        let tz = find_time_zone(tz)?;
+        let events: Vec<OutgoingEvent> = self
+            .iter()
+            .map(|block| OutgoingEvent {
+                block,
+                sequence: 0,
+                cancelled: false,
+            })
+            .collect();
+        Ok(write_ics(date, tz, None, &dtstamp_now(), &events))
+    }
+    /// 相对上一次导出的日程 `previous` 生成增量更新（METHOD:REQUEST），`tz` 与 `to_ics` 相同
+    ///
+    /// `sequences` 记录各事件（按 UID）上一次导出的 SEQUENCE，调用方在多次导出之间保存它。
+    /// 与 `Schedule::diff` 一致，名称和起止时间相同但其他字段不同的时间段视为修改，其 SEQUENCE
+    /// 在上一次的基础上加一；其余事件沿用上一次的值（新事件为 0）。UID 由名称和起止时间生成，
+    /// 改期或删除的时间段在新时间下是另一个事件，旧事件以 STATUS:CANCELLED 输出（SEQUENCE 加一），
+    /// 日历应用据此删除旧事件。导出后 `sequences` 更新为本次的值。
+    pub fn to_ics_update(
+        &self,
+        previous: &Schedule,
+        date: (u16, u8, u8),
+        tz: Option<&str>,
+        sequences: &mut HashMap<String, u32>,
+    ) -> Result<String, ScheduleError> {
+        let tz = find_time_zone(tz)?;
+        let diff = previous.diff(self);
+        let modified: Vec<&Block> = diff.modified.iter().map(|(_, new)| new).collect();
+        let mut events = Vec::new();
+        for block in self {
+            let sequence = sequences.entry(event_uid(date, block)).or_insert(0);
+            if modified.contains(&block) {
+                *sequence += 1;
+            }
+            events.push(OutgoingEvent {
+                block,
+                sequence: *sequence,
+                cancelled: false,
+            });
+        }
+        for block in &diff.removed {
+            let sequence = sequences.entry(event_uid(date, block)).or_insert(0);
+            *sequence += 1;
+            events.push(OutgoingEvent {
+                block,
+                sequence: *sequence,
+                cancelled: true,
+            });
+        }
+        Ok(write_ics(date, tz, Some("REQUEST"), &dtstamp_now(), &events))
+    }
+}
+
+/// 导出的一个事件
+struct OutgoingEvent<'a> {
+    block: &'a Block,
+    sequence: u32,
+    /// 是否以 STATUS:CANCELLED 输出（取消日历中已有的事件）
+    cancelled: bool,
+}
+
+/// 按名称查找支持的时区，None（浮动时间）返回 Ok(None)，未知时区返回错误
+fn find_time_zone(tz: Option<&str>) -> Result<Option<&'static TimeZone>, ScheduleError> {
+    match tz {
+        None => Ok(None),
+        Some(tz) => TIME_ZONES
+            .iter()
+            .find(|zone| zone.name == tz)
+            .map(Some)
+            .ok_or_else(|| ScheduleError::UnknownTimeZone(tz.to_string())),
+    }
+}
+
+/// 生成 iCalendar 文本，`dtstamp` 为 UTC 的生成时间；使用时区时附带对应的 VTIMEZONE，过长的行按 RFC 5545 折行
+fn write_ics(
+    date: (u16, u8, u8),
+    tz: Option<&TimeZone>,
+    method: Option<&str>,
+    dtstamp: &str,
+    events: &[OutgoingEvent],
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//TimeBlock//TimeBlock//ZH".to_string(),
+    ];
+    if let Some(method) = method {
+        lines.push(format!("METHOD:{}", method));
+    }
+    if let Some(tz) = tz {
+        lines.extend(tz.vtimezone());
+    }
+    let tz = tz.map(|tz| tz.name);
+    for event in events {
+        let block = event.block;
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event_uid(date, block)));
+        lines.push(format!("DTSTAMP:{}", dtstamp));
+        lines.push(format!("SEQUENCE:{}", event.sequence));
+        if event.cancelled {
+            lines.push("STATUS:CANCELLED".to_string());
+        }
+        lines.push(format!("DTSTART{}", format_date_time(date, block.start_time(), tz)));
+        lines.push(format!("DTEND{}", format_date_time(date, block.end_time(), tz)));
+        lines.push(format!("SUMMARY:{}", escape_text(block.name())));
+        if let Some(description) = block.description() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold_line(line)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+}
+
+/// 把超过 75 字节的内容行折成多行，续行以一个空格开头；只在字符边界处断开
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+impl Schedule {
+    /// 从 iCalendar 文本导入 `date`（年, 月, 日）当天的事件
+    ///
+    /// SUMMARY 作为名称，DESCRIPTION 作为描述；事件内嵌套的组件（如 VALARM）的属性不会覆盖事件本身。
+    /// 已取消（STATUS:CANCELLED）的事件直接忽略。
+    /// 没有 SUMMARY 的事件、跨天的事件、全天事件（没有具体时间）和使用 UTC 时间的事件会被跳过并记录警告；其他日期的事件
+    /// 直接忽略。时间格式错误或事件相互重叠时返回带行号的解析错误。
+    pub fn from_ics(input: &str, date: (u16, u8, u8)) -> Result<Schedule, ScheduleError> {
+        let mut schedule = Schedule::new();
+        let mut event: Option<Event> = None;
+        for (line, content) in unfold_lines(input) {
+            let Some((name, value)) = split_property(&content) else {
+                continue;
+            };
+            // 嵌套在事件内的组件：只跟踪层级，其属性不属于事件
+            if let Some(event) = event.as_mut() {
+                match name.as_str() {
+                    "BEGIN" => {
+                        event.depth += 1;
+                        continue;
+                    }
+                    "END" if event.depth > 0 => {
+                        event.depth -= 1;
+                        continue;
+                    }
+                    _ if event.depth > 0 => continue,
+                    _ => {}
+                }
+            }
+            match (name.as_str(), event.as_mut()) {
+                ("BEGIN", _) if value == "VEVENT" => {
+                    event = Some(Event {
+                        line,
+                        ..Event::default()
+                    })
+                }
+                ("END", Some(_)) if value == "VEVENT" => {
+                    let finished = event.take().expect("已在事件内");
+                    let line = finished.line;
+                    if let Some(block) = finished.into_block(date).map_err(|reason| ScheduleError::Parse { line, reason })? {
+                        schedule
+                            .add_block(block)
+                            .map_err(|e| ScheduleError::Parse { line, reason: e.to_string() })?;
+                    }
+                }
+                ("DTSTART", Some(event)) => {
+                    event.utc |= value.ends_with('Z');
+                    event.start = Some(parse_date_time(value).ok_or_else(|| invalid_date_time(line, value))?)
+                }
+                ("DTEND", Some(event)) => {
+                    event.utc |= value.ends_with('Z');
+                    event.end = Some(parse_date_time(value).ok_or_else(|| invalid_date_time(line, value))?)
+                }
+                ("SUMMARY", Some(event)) => event.summary = Some(unescape_text(value)),
+                ("STATUS", Some(event)) => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+                ("DESCRIPTION", Some(event)) => event.description = Some(unescape_text(value)),
+                _ => {}
+            }
+        }
+        schedule.clear_history();
+        Ok(schedule)
+    }
+}
+
+/// 日期与可选的时间（全天事件只有日期）
+type DateTime = ((u16, u8, u8), Option<TimeOfDay>);
+
+/// 导入过程中正在读取的 VEVENT
+#[derive(Default)]
+struct Event {
+    /// BEGIN:VEVENT 所在行号
+    line: usize,
+    /// 当前所在的嵌套组件层数，0 表示事件本身
+    depth: usize,
+    /// DTSTART 或 DTEND 使用了 UTC 时间
+    utc: bool,
+    /// STATUS:CANCELLED，已取消的事件
+    cancelled: bool,
+    start: Option<DateTime>,
+    end: Option<DateTime>,
+    summary: Option<String>,
+    description: Option<String>,
+}
+
+impl Event {
+    /// 转换为 `date` 当天的时间段；不在当天或需要跳过的事件返回 None
+    fn into_block(self, date: (u16, u8, u8)) -> Result<Option<Block>, String> {
+        let summary = self.summary.unwrap_or_default();
+        let (Some((start_date, start)), Some((end_date, end))) = (self.start, self.end) else {
+            warn!("事件 {}（第 {} 行）缺少 DTSTART 或 DTEND，已跳过", summary, self.line);
+            return Ok(None);
+        };
+        if (start_date != date && end_date != date) || self.cancelled {
+            return Ok(None);
+        }
+        if summary.trim().is_empty() {
+            warn!("事件（第 {} 行）没有 SUMMARY，已跳过", self.line);
+            return Ok(None);
+        }
+        if start_date != end_date {
+            warn!("事件 {}（第 {} 行）跨越多天，已跳过", summary, self.line);
+            return Ok(None);
+        }
+        if self.utc {
+            warn!("事件 {}（第 {} 行）使用 UTC 时间，无法换算为本地时间，已跳过", summary, self.line);
+            return Ok(None);
+        }
+        let (Some(start), Some(end)) = (start, end) else {
+            warn!("事件 {}（第 {} 行）没有具体时间，已跳过", summary, self.line);
+            return Ok(None);
+        };
+        Block::builder()
+            .start_time(start)
+            .end_time(end)
+            .name(summary)
+            .description(self.description.filter(|d| !d.is_empty()))
+            .is_fixed(false)
+            .build()
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// 展开折行（以空格或制表符开头的行接在上一行之后），返回每个逻辑行及其起始行号
+fn unfold_lines(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, raw) in input.split('\n').enumerate() {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some((_, last))) => last.push_str(continuation),
+            _ => lines.push((index + 1, raw.to_string())),
+        }
+    }
+    lines
+}
+
+/// 拆分属性行为 (大写的属性名, 值)，忽略属性参数；不含冒号的行返回 None
+///
+/// 名称与值以第一个不在双引号内的冒号分隔，参数值可以是带冒号的带引号字符串（如 `ALTREP="cid:…"`）。
+fn split_property(content: &str) -> Option<(String, &str)> {
+    let mut quoted = false;
+    let colon = content.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&content[..colon], &content[colon + 1..]);
+    let name = head.split(';').next().unwrap_or(head);
+    Some((name.trim().to_ascii_uppercase(), value.trim()))
+}
+
+/// 解析 `20240601T090000`、`20240601T090000Z` 或全天的 `20240601`，秒数忽略，`Z` 由调用方处理
+fn parse_date_time(value: &str) -> Option<DateTime> {
+    let value = value.strip_suffix('Z').unwrap_or(value);
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let (date, time) = match value.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (value, None),
+    };
+    if date.len() != 8 || !digits(date) {
+        return None;
+    }
+    let year = date[..4].parse().ok()?;
+    let month = date[4..6].parse().ok()?;
+    let day = date[6..].parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let time = match time {
+        Some(time) => {
+            if time.len() != 6 || !digits(time) {
+                return None;
+            }
+            let hour: u16 = time[..2].parse().ok()?;
+            let minute: u16 = time[2..4].parse().ok()?;
+            if hour > 23 || minute > 59 {
+                return None;
+            }
+            Some(TimeOfDay::try_from(hour * 60 + minute).ok()?)
+        }
+        None => None,
+    };
+    Some(((year, month, day), time))
+}
+
+/// 日期时间格式错误
+fn invalid_date_time(line: usize, value: &str) -> ScheduleError {
+    ScheduleError::Parse {
+        line,
+        reason: format!("日期时间格式不正确：{}", value),
+    }
+}
+
+/// 按 RFC 5545 反转义文本值
+fn unescape_text(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => output.push('\n'),
+            Some(escaped) => output.push(escaped),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+/// 事件的稳定 UID，例如 `20240601-3f2a…@timeblock`
+fn event_uid(date: (u16, u8, u8), block: &Block) -> String {
+    let (year, month, day) = date;
+    let key = format!("{}|{}|{}", block.name(), block.start_time(), block.end_time());
+    format!("{:04}{:02}{:02}-{:016x}@timeblock", year, month, day, utils::stable_hash(&key))
+}
+
+/// 当前时间的 DTSTAMP 值
+fn dtstamp_now() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    format_utc(secs)
+}
+
+/// 把 Unix 时间戳格式化为 UTC 时间，例如 `20240601T090000Z`
+fn format_utc(secs: u64) -> String {
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+    // 公历换算（Howard Hinnant 的 civil_from_days），以 0000-03-01 起算的 400 年周期
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// 格式化属性参数与值，例如 `;TZID=Asia/Shanghai:20240601T090000`
+fn format_date_time(date: (u16, u8, u8), time: TimeOfDay, tz: Option<&str>) -> String {
+    let (year, month, day) = date;
+    let value = format!("{:04}{:02}{:02}T{:02}{:02}00", year, month, day, time.hour(), time.minute());
+    match tz {
+        Some(tz) => format!(";TZID={}:{}", tz, value),
+        None => format!(":{}", value),
+    }
+}
+
+/// 按 RFC 5545 转义文本值
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::block::Block;
+
+    fn sample() -> Schedule {
+        let mut schedule = Schedule::new();
+        schedule
+            .add_block(
+                Block::builder()
+                    .start_time(TimeOfDay::new(9, 0).unwrap())
+                    .end_time(TimeOfDay::new(10, 30).unwrap())
+                    .name("会议".to_string())
+                    .description(Some("议题：进度, 风险".to_string()))
+                    .is_fixed(true)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        schedule
+    }
+
+    #[test]
+    fn test_to_ics_floating_time() {
+        let ics = sample().to_ics((2024, 6, 1), None).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20240601T090000\r\n"));
+        assert!(ics.contains("DTEND:20240601T103000\r\n"));
+        assert!(ics.contains("SUMMARY:会议\r\n"));
+        assert!(ics.contains("DESCRIPTION:议题：进度\\, 风险\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    /// 去掉随生成时间变化的 DTSTAMP 行
+    fn without_dtstamp(ics: &str) -> String {
+        ics.split("\r\n").filter(|l| !l.starts_with("DTSTAMP:")).collect::<Vec<_>>().join("\r\n")
+    }
+
+    #[test]
+    fn test_to_ics_stable_uid() {
+        let first = sample().to_ics((2024, 6, 1), None).unwrap();
+        let second = sample().to_ics((2024, 6, 1), None).unwrap();
+        assert_eq!(without_dtstamp(&first), without_dtstamp(&second));
+        assert!(first.contains("\r\nUID:20240601-"));
+        assert!(first.contains("\r\nSEQUENCE:0\r\n"));
+        assert!(!first.contains("METHOD:"));
+    }
+
+    #[test]
+    fn test_to_ics_update_bumps_changed_sequence() {
+        let previous = Schedule::from_text("09:00-10:00 晨会\n14:00-15:00 评审 | 初稿\n").unwrap();
+        let current = Schedule::from_text("09:00-10:00 晨会\n14:00-15:00 评审 | 终稿\n16:00-17:00 复盘\n").unwrap();
+        let mut sequences = HashMap::new();
+        let ics = current.to_ics_update(&previous, (2024, 6, 1), None, &mut sequences).unwrap();
+        assert!(ics.contains("\r\nMETHOD:REQUEST\r\n"));
+
+        assert_eq!(sequence_of(&ics, "晨会").1, "SEQUENCE:0");
+        assert_eq!(sequence_of(&ics, "评审").1, "SEQUENCE:1");
+        assert_eq!(sequence_of(&ics, "复盘").1, "SEQUENCE:0");
+        // 修改描述不改变 UID
+        let full = previous.to_ics((2024, 6, 1), None).unwrap();
+        assert!(full.contains(&sequence_of(&ics, "评审").0));
+        assert_eq!(sequences.len(), 3);
+    }
+
+    #[test]
+    fn test_to_ics_update_sequence_across_rounds() {
+        let first = Schedule::from_text("09:00-10:00 晨会 | 议程\n14:00-15:00 评审 | 初稿\n").unwrap();
+        let second = Schedule::from_text("09:00-10:00 晨会 | 议程\n14:00-15:00 评审 | 二稿\n").unwrap();
+        let third = Schedule::from_text("09:00-10:00 晨会 | 新议程\n14:00-15:00 评审 | 终稿\n").unwrap();
+        let fourth = Schedule::from_text("09:00-10:00 晨会 | 新议程\n14:00-15:00 评审 | 终稿\n").unwrap();
+        let mut sequences = HashMap::new();
+
+        let ics = second.to_ics_update(&first, (2024, 6, 1), None, &mut sequences).unwrap();
+        assert_eq!(sequence_of(&ics, "晨会").1, "SEQUENCE:0");
+        assert_eq!(sequence_of(&ics, "评审").1, "SEQUENCE:1");
+        // 第二次修改继续递增
+        let ics = third.to_ics_update(&second, (2024, 6, 1), None, &mut sequences).unwrap();
+        assert_eq!(sequence_of(&ics, "晨会").1, "SEQUENCE:1");
+        assert_eq!(sequence_of(&ics, "评审").1, "SEQUENCE:2");
+        // 未修改的事件保持原值，不会回落
+        let ics = fourth.to_ics_update(&third, (2024, 6, 1), None, &mut sequences).unwrap();
+        assert_eq!(sequence_of(&ics, "晨会").1, "SEQUENCE:1");
+        assert_eq!(sequence_of(&ics, "评审").1, "SEQUENCE:2");
+    }
+
+    #[test]
+    fn test_to_ics_update_cancels_rescheduled_event() {
+        let previous = Schedule::from_text("09:00-10:00 晨会\n14:00-15:00 评审\n").unwrap();
+        let current = Schedule::from_text("09:30-10:30 晨会\n").unwrap();
+        let mut sequences = HashMap::new();
+        let ics = current.to_ics_update(&previous, (2024, 6, 1), Some("Asia/Shanghai"), &mut sequences).unwrap();
+
+        let events: Vec<&str> = ics.split("BEGIN:VEVENT").skip(1).collect();
+        assert_eq!(events.len(), 3);
+        // 改期后的新事件
+        assert!(events[0].contains("DTSTART;TZID=Asia/Shanghai:20240601T093000\r\n"));
+        assert!(events[0].contains("SEQUENCE:0\r\n"));
+        assert!(!events[0].contains("STATUS:CANCELLED"));
+        // 原来的晨会与删除的评审以旧 UID 取消
+        let old = previous.to_ics((2024, 6, 1), None).unwrap();
+        for event in &events[1..] {
+            let uid = event.lines().find(|l| l.starts_with("UID:")).unwrap();
+            assert!(old.contains(uid));
+            assert!(event.contains("SEQUENCE:1\r\nSTATUS:CANCELLED\r\n"));
+        }
+        assert!(events[1].contains("DTSTART;TZID=Asia/Shanghai:20240601T090000\r\n"));
+        assert!(events[2].contains("SUMMARY:评审\r\n"));
+        assert_eq!(sequences.len(), 3);
+        // 导入时忽略已取消的事件
+        assert_eq!(Schedule::from_ics(&ics, (2024, 6, 1)).unwrap().blocks(), current.blocks());
+
+        let result = current.to_ics_update(&previous, (2024, 6, 1), Some("Mars/Olympus_Mons"), &mut sequences);
+        assert!(matches!(result, Err(ScheduleError::UnknownTimeZone(_))));
+    }
+
+    /// 按名称找到事件，返回其 (UID 行, SEQUENCE 行)
+    fn sequence_of(ics: &str, name: &str) -> (String, String) {
+        let event = ics.split("BEGIN:VEVENT").find(|e| e.contains(&format!("SUMMARY:{}", name))).unwrap();
+        let uid = event.lines().find(|l| l.starts_with("UID:")).unwrap().to_string();
+        let sequence = event.lines().find(|l| l.starts_with("SEQUENCE:")).unwrap().to_string();
+        (uid, sequence)
+    }
+
+    #[test]
+    fn test_dtstamp() {
+        assert_eq!(format_utc(0), "19700101T000000Z");
+        assert_eq!(format_utc(1_717_232_400), "20240601T090000Z");
+        assert_eq!(format_utc(951_825_599), "20000229T115959Z");
+        let ics = sample().to_ics((2024, 6, 1), None).unwrap();
+        let stamp = ics.lines().find(|l| l.starts_with("DTSTAMP:")).unwrap();
+        assert_eq!(stamp.len(), "DTSTAMP:20240601T090000Z".len());
+        assert!(stamp.ends_with('Z'));
+    }
+
+    #[test]
+    fn test_to_ics_with_time_zone() {
+        let ics = sample().to_ics((2024, 6, 1), Some("Asia/Shanghai")).unwrap();
+        assert!(ics.contains("DTSTART;TZID=Asia/Shanghai:20240601T090000\r\n"));
+        assert!(ics.contains("DTEND;TZID=Asia/Shanghai:20240601T103000\r\n"));
+        // 引用的 TZID 必须有对应的 VTIMEZONE
+        assert!(ics.contains(
+            "BEGIN:VTIMEZONE\r\nTZID:Asia/Shanghai\r\nBEGIN:STANDARD\r\nDTSTART:19700101T000000\r\n\
+             TZOFFSETFROM:+0800\r\nTZOFFSETTO:+0800\r\nTZNAME:CST\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n"
+        ));
+        assert!(ics.find("END:VTIMEZONE").unwrap() < ics.find("BEGIN:VEVENT").unwrap());
+        assert!(!sample().to_ics((2024, 6, 1), None).unwrap().contains("VTIMEZONE"));
+    }
+
+    #[test]
+    fn test_vtimezone_with_daylight_saving() {
+        let ics = sample().to_ics((2024, 6, 1), Some("America/New_York")).unwrap();
+        assert!(ics.contains(
+            "BEGIN:DAYLIGHT\r\nDTSTART:19700308T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\n\
+             TZOFFSETFROM:-0500\r\nTZOFFSETTO:-0400\r\nTZNAME:EDT\r\nEND:DAYLIGHT\r\n"
+        ));
+        assert!(ics.contains(
+            "BEGIN:STANDARD\r\nDTSTART:19701101T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\n\
+             TZOFFSETFROM:-0400\r\nTZOFFSETTO:-0500\r\nTZNAME:EST\r\nEND:STANDARD\r\n"
+        ));
+        // 导入时忽略 VTIMEZONE 中的 DTSTART
+        let loaded = Schedule::from_ics(&ics, (2024, 6, 1)).unwrap();
+        assert!(loaded.blocks()[0].content_eq(&sample().blocks()[0]));
+    }
+
+    #[test]
+    fn test_to_ics_folds_long_lines() {
+        let description = "这是一段很长的会议说明，".repeat(10);
+        let schedule = Schedule::from_text(&format!("09:00-10:00 会议 | {}\n", description)).unwrap();
+        let ics = schedule.to_ics((2024, 6, 1), None).unwrap();
+        let lines: Vec<&str> = ics.split("\r\n").collect();
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert!(lines.iter().any(|line| line.starts_with(' ')));
+        let loaded = Schedule::from_ics(&ics, (2024, 6, 1)).unwrap();
+        assert_eq!(loaded.blocks()[0].description(), Some(description.trim_end()));
+
+        assert_eq!(fold_line("SUMMARY:短"), "SUMMARY:短");
+        assert_eq!(fold_line(&"a".repeat(76)), format!("{}\r\n a", "a".repeat(75)));
+    }
+
+    #[test]
+    fn test_to_ics_unknown_time_zone() {
+        let result = sample().to_ics((2024, 6, 1), Some("Mars/Olympus_Mons"));
+        assert!(matches!(result, Err(ScheduleError::UnknownTimeZone(tz)) if tz == "Mars/Olympus_Mons"));
+    }
+
+    #[test]
+    fn test_from_ics() {
+        let input = "BEGIN:VCALENDAR\r\n\
+            VERSION:2.0\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;TZID=Asia/Shanghai:20240601T140000\r\n\
+            DTEND;TZID=Asia/Shanghai:20240601T150000\r\n\
+            SUMMARY:评审\\, 设计\r\n\
+            DESCRIPTION:第一行\\n第二行\\; 结\r\n \
+            束\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20240601T090000\r\n\
+            DTEND:20240601T103000\r\n\
+            SUMMARY:会议\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20240601T120000Z\r\n\
+            DTEND:20240601T130000Z\r\n\
+            SUMMARY:UTC\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20240602T090000\r\n\
+            DTEND:20240602T100000\r\n\
+            SUMMARY:第二天\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20240601\r\n\
+            DTEND;VALUE=DATE:20240601\r\n\
+            SUMMARY:全天\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20240601T230000\r\n\
+            DTEND:20240602T010000\r\n\
+            SUMMARY:跨天\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let schedule = Schedule::from_ics(input, (2024, 6, 1)).unwrap();
+
+        assert_eq!(schedule.len(), 2);
+        let first = &schedule.blocks()[0];
+        assert_eq!(first.name(), "会议");
+        assert_eq!(first.start_time(), TimeOfDay::new(9, 0).unwrap());
+        assert_eq!(first.end_time(), TimeOfDay::new(10, 30).unwrap());
+        assert_eq!(first.description(), None);
+        let second = &schedule.blocks()[1];
+        assert_eq!(second.name(), "评审, 设计");
+        assert_eq!(second.description(), Some("第一行\n第二行; 结束"));
+        assert!(!schedule.can_undo());
+    }
+
+    #[test]
+    fn test_from_ics_round_trip() {
+        let schedule = sample();
+        let ics = schedule.to_ics((2024, 6, 1), Some("Asia/Shanghai")).unwrap();
+        let loaded = Schedule::from_ics(&ics, (2024, 6, 1)).unwrap();
+        assert!(loaded.blocks()[0].content_eq(&schedule.blocks()[0]));
+    }
+
+    #[test]
+    fn test_from_ics_nested_alarm() {
+        let input = "BEGIN:VCALENDAR\n\
+            BEGIN:VEVENT\n\
+            DTSTART:20240601T090000\n\
+            DTEND:20240601T100000\n\
+            SUMMARY:会议\n\
+            DESCRIPTION:议程\n\
+            BEGIN:VALARM\n\
+            ACTION:DISPLAY\n\
+            DESCRIPTION:Reminder\n\
+            TRIGGER:-PT15M\n\
+            END:VALARM\n\
+            END:VEVENT\n\
+            END:VCALENDAR\n";
+        let schedule = Schedule::from_ics(input, (2024, 6, 1)).unwrap();
+        assert_eq!(schedule.len(), 1);
+        assert_eq!(schedule.blocks()[0].name(), "会议");
+        assert_eq!(schedule.blocks()[0].description(), Some("议程"));
+        assert_eq!(schedule.blocks()[0].end_time(), TimeOfDay::new(10, 0).unwrap());
+    }
+
+    #[test]
+    fn test_from_ics_skips_event_without_summary() {
+        let input = "BEGIN:VCALENDAR\n\
+            BEGIN:VEVENT\n\
+            DTSTART:20240601T080000\n\
+            DTEND:20240601T083000\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            DTSTART:20240601T090000\n\
+            DTEND:20240601T100000\n\
+            SUMMARY:会议\n\
+            END:VEVENT\n\
+            END:VCALENDAR\n";
+        let schedule = Schedule::from_ics(input, (2024, 6, 1)).unwrap();
+        assert_eq!(schedule.len(), 1);
+        assert_eq!(schedule.blocks()[0].name(), "会议");
+    }
+
+    #[test]
+    fn test_from_ics_quoted_parameter() {
+        let input = "BEGIN:VEVENT\n\
+            DTSTART:20240601T090000\n\
+            DTEND:20240601T100000\n\
+            SUMMARY:会议\n\
+            DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\":text\n\
+            END:VEVENT\n";
+        let schedule = Schedule::from_ics(input, (2024, 6, 1)).unwrap();
+        assert_eq!(schedule.blocks()[0].description(), Some("text"));
+        assert_eq!(
+            split_property("DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\":text"),
+            Some(("DESCRIPTION".to_string(), "text"))
+        );
+    }
+
+    #[test]
+    fn test_from_ics_malformed_time() {
+        let input = "BEGIN:VEVENT\nDTSTART:20240601T0900\nEND:VEVENT\n";
+        let result = Schedule::from_ics(input, (2024, 6, 1));
+        assert!(matches!(result, Err(ScheduleError::Parse { line: 2, .. })));
+    }
+}
@@ -0,0 +1,151 @@
+//! 日程检查：不阻止保存、只提示改进的非致命问题
+
+use std::fmt;
+
+use crate::data::block::BREAK_NAME;
+use crate::data::day::Schedule;
+use crate::data::timeofday::TimeOfDay;
+use crate::utils::{self, Locale};
+
+/// 短于此分钟数的时间段视为过短
+const MIN_REASONABLE_MINUTES: u16 = 5;
+/// 长于此分钟数的时间段视为过长
+const MAX_REASONABLE_MINUTES: u16 = 4 * 60;
+/// 连续安排达到此分钟数而没有休息时提示
+const MAX_STRETCH_MINUTES: u16 = 6 * 60;
+
+/// 日程中的非致命问题，由 `Schedule::lint` 给出
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleWarning {
+    /// 第 `index` 个时间段短于 5 分钟
+    TooShort { index: usize, minutes: u16 },
+    /// 第 `index` 个时间段长于 4 小时
+    TooLong { index: usize, minutes: u16 },
+    /// [start, end) 内的时间段首尾相接，连续 6 小时以上没有空闲或休息
+    NoBreak { start: TimeOfDay, end: TimeOfDay },
+    /// 第 `index` 个时间段是固定的（如会议）但没有描述
+    FixedWithoutDescription { index: usize },
+}
+
+impl fmt::Display for ScheduleWarning {
+    /// 按当前语言格式化提示信息
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, utils::locale()) {
+            (ScheduleWarning::TooShort { index, minutes }, Locale::ZhCn) => {
+                write!(f, "第 {} 个时间段只有 {} 分钟，可能过短", index + 1, minutes)
+            }
+            (ScheduleWarning::TooShort { index, minutes }, Locale::En) => {
+                write!(f, "Block {} is only {} minutes long and may be too short", index + 1, minutes)
+            }
+            (ScheduleWarning::TooLong { index, minutes }, Locale::ZhCn) => {
+                write!(f, "第 {} 个时间段长达 {} 分钟，建议拆分", index + 1, minutes)
+            }
+            (ScheduleWarning::TooLong { index, minutes }, Locale::En) => {
+                write!(f, "Block {} is {} minutes long; consider splitting it", index + 1, minutes)
+            }
+            (ScheduleWarning::NoBreak { start, end }, Locale::ZhCn) => {
+                write!(f, "{}-{} 连续安排，中间没有休息", start, end)
+            }
+            (ScheduleWarning::NoBreak { start, end }, Locale::En) => {
+                write!(f, "{}-{} is scheduled back to back without a break", start, end)
+            }
+            (ScheduleWarning::FixedWithoutDescription { index }, Locale::ZhCn) => {
+                write!(f, "第 {} 个时间段是固定的，但没有描述", index + 1)
+            }
+            (ScheduleWarning::FixedWithoutDescription { index }, Locale::En) => {
+                write!(f, "Block {} is fixed but has no description", index + 1)
+            }
+        }
+    }
+}
+
+impl Schedule {
+    /// 检查日程中的非致命问题：过短或过长的时间段、长时间连续安排没有休息、没有描述的固定时间段
+    ///
+    /// 同一类问题按时间顺序排列。首尾相接的时间段视为连续，空闲时间或名为“休息”的时间段会打断连续。
+    pub fn lint(&self) -> Vec<ScheduleWarning> {
+        let mut warnings = Vec::new();
+        for (index, block) in self.iter().enumerate() {
+            let minutes = block.duration().minutes();
+            if minutes < MIN_REASONABLE_MINUTES {
+                warnings.push(ScheduleWarning::TooShort { index, minutes });
+            }
+            if minutes > MAX_REASONABLE_MINUTES {
+                warnings.push(ScheduleWarning::TooLong { index, minutes });
+            }
+        }
+        // 跳过休息后，休息两侧的时间段不再首尾相接，自然分成两段
+        let mut stretches: Vec<(TimeOfDay, TimeOfDay)> = Vec::new();
+        for block in self.iter().filter(|b| b.name() != BREAK_NAME) {
+            match stretches.last_mut() {
+                Some((_, end)) if *end == block.start_time() => *end = block.end_time(),
+                _ => stretches.push((block.start_time(), block.end_time())),
+            }
+        }
+        warnings.extend(
+            stretches
+                .into_iter()
+                .filter(|(start, end)| u16::from(*end) - u16::from(*start) >= MAX_STRETCH_MINUTES)
+                .map(|(start, end)| ScheduleWarning::NoBreak { start, end }),
+        );
+        for (index, block) in self.iter().enumerate() {
+            if block.is_fixed() && block.description().is_none_or(|d| d.trim().is_empty()) {
+                warnings.push(ScheduleWarning::FixedWithoutDescription { index });
+            }
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::block::Block;
+
+    fn parse(input: &str) -> Schedule {
+        Schedule::from_text(input).unwrap()
+    }
+
+    fn t(hour: u16, minute: u16) -> TimeOfDay {
+        TimeOfDay::new(hour, minute).unwrap()
+    }
+
+    #[test]
+    fn test_lint_clean_schedule() {
+        let schedule = parse("09:00-10:00 晨会\n10:00-12:00 写代码\n13:00-14:00 评审\n");
+        assert!(schedule.lint().is_empty());
+    }
+
+    #[test]
+    fn test_lint_block_length() {
+        let schedule = parse("09:00-09:03 打卡\n10:00-14:30 写代码\n");
+        assert_eq!(
+            schedule.lint(),
+            vec![
+                ScheduleWarning::TooShort { index: 0, minutes: 3 },
+                ScheduleWarning::TooLong { index: 1, minutes: 270 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_no_break() {
+        let schedule = parse("08:00-10:00 写代码\n10:00-12:00 评审\n12:00-14:00 写文档\n15:00-16:00 回邮件\n");
+        assert_eq!(schedule.lint(), vec![ScheduleWarning::NoBreak { start: t(8, 0), end: t(14, 0) }]);
+
+        // 中间的休息打断连续安排
+        let schedule = parse("08:00-10:00 写代码\n10:00-10:15 休息\n10:15-12:00 评审\n12:00-14:00 写文档\n");
+        assert!(schedule.lint().is_empty());
+    }
+
+    #[test]
+    fn test_lint_fixed_without_description() {
+        let mut schedule = parse("10:00-11:00 写代码\n");
+        schedule.add_block(Block::meeting(t(9, 0), t(10, 0), "周会".to_string()).unwrap()).unwrap();
+        assert_eq!(schedule.lint(), vec![ScheduleWarning::FixedWithoutDescription { index: 0 }]);
+        let _locale = utils::lock_locale();
+        assert_eq!(schedule.lint()[0].to_string(), "第 1 个时间段是固定的，但没有描述");
+        utils::set_locale(Locale::En);
+        assert_eq!(schedule.lint()[0].to_string(), "Block 1 is fixed but has no description");
+    }
+}
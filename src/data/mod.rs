@@ -1,5 +1,12 @@
-// src/data/mod.rs
-mod block;
-mod day;
-mod week;
-mod timeofday;
\ No newline at end of file
+pub mod block;
+pub mod day;
+pub mod duration;
+pub mod export;
+pub mod ics;
+pub mod lint;
+pub mod plan;
+pub mod storage;
+pub mod template;
+pub mod text;
+pub mod week;
+pub mod timeofday;
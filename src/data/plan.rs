@@ -0,0 +1,117 @@
+//! 轮换使用的多套日程方案（如学校的 A/B 日、倒班）
+
+use crate::data::day::{Schedule, ScheduleError};
+
+/// 按名称管理的多套日程方案，其中一套为当前方案
+///
+/// 方案按添加顺序排列，`rotate` 依此顺序循环切换。
+#[derive(Debug, Clone)]
+pub struct PlanSet {
+    /// 方案名称与对应日程，名称互不相同
+    plans: Vec<(String, Schedule)>,
+    /// 当前方案在 `plans` 中的下标
+    active: usize,
+}
+
+impl PlanSet {
+    /// 以第一套方案创建，它同时成为当前方案
+    pub fn new(name: impl Into<String>, schedule: Schedule) -> PlanSet {
+        PlanSet {
+            plans: vec![(name.into(), schedule)],
+            active: 0,
+        }
+    }
+    /// 添加方案；同名方案已存在时替换其日程并返回旧日程
+    pub fn add_plan(&mut self, name: impl Into<String>, schedule: Schedule) -> Option<Schedule> {
+        let name = name.into();
+        match self.plans.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => Some(std::mem::replace(existing, schedule)),
+            None => {
+                self.plans.push((name, schedule));
+                None
+            }
+        }
+    }
+    /// 当前方案的名称
+    pub fn active_plan(&self) -> &str {
+        &self.plans[self.active].0
+    }
+    /// 切换到指定名称的方案，名称不存在时返回错误
+    pub fn set_active(&mut self, name: &str) -> Result<(), ScheduleError> {
+        self.active = self
+            .plans
+            .iter()
+            .position(|(n, _)| n == name)
+            .ok_or_else(|| ScheduleError::UnknownPlan(name.to_string()))?;
+        Ok(())
+    }
+    /// 当前方案的日程
+    pub fn current(&self) -> &Schedule {
+        &self.plans[self.active].1
+    }
+    /// 当前方案的日程（可修改）
+    pub fn current_mut(&mut self) -> &mut Schedule {
+        &mut self.plans[self.active].1
+    }
+    /// 按名称获取方案的日程
+    pub fn get(&self, name: &str) -> Option<&Schedule> {
+        self.plans.iter().find(|(n, _)| n == name).map(|(_, schedule)| schedule)
+    }
+    /// 切换到下一套方案（最后一套之后回到第一套），返回新的当前方案名称
+    pub fn rotate(&mut self) -> &str {
+        self.active = (self.active + 1) % self.plans.len();
+        self.active_plan()
+    }
+    /// 全部方案名称，按添加顺序
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.plans.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plans() -> PlanSet {
+        let mut plans = PlanSet::new("A", Schedule::from_text("08:00-09:30 数学\n").unwrap());
+        plans.add_plan("B", Schedule::from_text("08:00-09:30 语文\n10:00-11:00 体育\n").unwrap());
+        plans
+    }
+
+    #[test]
+    fn test_switch_plans() {
+        let mut plans = plans();
+        assert_eq!(plans.active_plan(), "A");
+        assert_eq!(plans.current().blocks()[0].name(), "数学");
+
+        plans.set_active("B").unwrap();
+        assert_eq!(plans.active_plan(), "B");
+        assert_eq!(plans.current().len(), 2);
+
+        let result = plans.set_active("C");
+        assert!(matches!(result, Err(ScheduleError::UnknownPlan(name)) if name == "C"));
+        assert_eq!(plans.active_plan(), "B");
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut plans = plans();
+        plans.add_plan("C", Schedule::new());
+        assert_eq!(plans.rotate(), "B");
+        assert_eq!(plans.rotate(), "C");
+        assert_eq!(plans.rotate(), "A");
+        assert_eq!(plans.names().collect::<Vec<_>>(), vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_add_plan_replaces_same_name() {
+        let mut plans = plans();
+        let old = plans.add_plan("A", Schedule::new()).unwrap();
+        assert_eq!(old.len(), 1);
+        assert!(plans.current().is_empty());
+        assert_eq!(plans.names().count(), 2);
+        let gym = plans.get("B").unwrap().blocks()[1].clone();
+        plans.current_mut().add_block(gym).unwrap();
+        assert_eq!(plans.current().len(), 1);
+    }
+}
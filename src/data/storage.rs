@@ -0,0 +1,252 @@
+//! JSON 文件存储
+//!
+//! 只保存时间段本身，撤销历史等运行时状态不写入文件。读取时每个时间段都会重新校验，
+//! 手工修改过的文件不会破坏日程的排序与不重叠约束。
+//!
+//! 文件格式为 `{ "version": 1, "day": { "blocks": [...] } }`。读取旧版本时逐版迁移到当前版本，
+//! 比程序更新的版本返回 `ScheduleError::UnsupportedVersion`。
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::data::block::Block;
+use crate::data::day::{Schedule, ScheduleError};
+use crate::data::timeofday::TimeOfDay;
+
+/// 当前的文件格式版本，格式变化时加一并在 `migrate` 中补充迁移
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// 带版本号的文件外层结构
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    version: u32,
+    day: DayRecord,
+}
+
+/// 只读取版本号，缺少时视为加入版本号之前的版本 0
+#[derive(Deserialize)]
+struct VersionProbe {
+    #[serde(default)]
+    version: u32,
+}
+
+/// 文件中的一日日程
+#[derive(Serialize, Deserialize)]
+struct DayRecord {
+    blocks: Vec<BlockRecord>,
+}
+
+/// 文件中的单个时间段
+#[derive(Serialize, Deserialize)]
+struct BlockRecord {
+    start_time: TimeOfDay,
+    end_time: TimeOfDay,
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    is_fixed: bool,
+    #[serde(default)]
+    locked: bool,
+    #[serde(default)]
+    priority: u8,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_description_len: Option<usize>,
+    #[serde(default)]
+    id: Option<String>,
+}
+
+impl From<&Block> for BlockRecord {
+    fn from(block: &Block) -> BlockRecord {
+        BlockRecord {
+            start_time: block.start_time(),
+            end_time: block.end_time(),
+            name: block.name().to_string(),
+            description: block.description().map(str::to_string),
+            is_fixed: block.is_fixed(),
+            locked: block.is_locked(),
+            priority: block.priority(),
+            tags: block.tags().to_vec(),
+            color: block.color().map(str::to_string),
+            max_description_len: block.max_description_len(),
+            id: block.id().map(str::to_string),
+        }
+    }
+}
+
+impl Schedule {
+    /// 序列化为 JSON 文本
+    pub fn to_json(&self) -> Result<String, ScheduleError> {
+        let envelope = Envelope {
+            version: SCHEMA_VERSION,
+            day: DayRecord {
+                blocks: self.iter().map(BlockRecord::from).collect(),
+            },
+        };
+        Ok(serde_json::to_string_pretty(&envelope)?)
+    }
+    /// 从 JSON 文本解析日程，旧版本先迁移到当前版本，每个时间段都经过校验
+    pub fn from_json(json: &str) -> Result<Schedule, ScheduleError> {
+        let value: Value = serde_json::from_str(json)?;
+        let envelope: Envelope = serde_json::from_value(migrate(value)?)?;
+        let record = envelope.day;
+        let mut schedule = Schedule::new();
+        for block in record.blocks {
+            let block = Block::builder()
+                .start_time(block.start_time)
+                .end_time(block.end_time)
+                .name(block.name)
+                .description(block.description)
+                .is_fixed(block.is_fixed)
+                .locked(block.locked)
+                .priority(block.priority)
+                .tags(block.tags)
+                .color(block.color)
+                .max_description_len(block.max_description_len)
+                .id(block.id)
+                .build()?;
+            schedule.add_block(block)?;
+        }
+        schedule.clear_history();
+        Ok(schedule)
+    }
+    /// 保存到 JSON 文件
+    ///
+    /// 先写入同目录下的临时文件再重命名，写入中途失败不会损坏原文件。
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), ScheduleError> {
+        write_file_atomic(path.as_ref(), &self.to_json()?)
+    }
+    /// 从 JSON 文件读取日程
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Schedule, ScheduleError> {
+        Schedule::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+/// 先写入同目录下的临时文件再重命名，写入中途失败不会损坏原文件
+pub(crate) fn write_file_atomic(path: &Path, contents: &str) -> Result<(), ScheduleError> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// 把任意版本的文件内容逐版迁移到 `SCHEMA_VERSION`
+fn migrate(mut value: Value) -> Result<Value, ScheduleError> {
+    let VersionProbe { mut version } = serde_json::from_value(value.clone())?;
+    if version > SCHEMA_VERSION {
+        return Err(ScheduleError::UnsupportedVersion {
+            found: version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    while version < SCHEMA_VERSION {
+        value = match version {
+            // 版本 0：没有外层结构，直接是一日日程
+            0 => json!({ "version": 1, "day": value }),
+            _ => unreachable!("版本 {} 缺少迁移", version),
+        };
+        version += 1;
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    fn sample() -> Schedule {
+        Schedule::from_text("09:00-10:30 会议 | 项目讨论\n12:00-13:00 午饭\n").unwrap()
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let schedule = sample();
+        let json = schedule.to_json().unwrap();
+        assert!(json.contains("\"start_time\": \"09:00\""));
+        let loaded = Schedule::from_json(&json).unwrap();
+        assert_eq!(loaded.blocks(), schedule.blocks());
+        assert!(!loaded.can_undo());
+    }
+
+    #[test]
+    fn test_round_trip_keeps_description_limit() {
+        let mut schedule = sample();
+        let limited = schedule.blocks()[1].to_builder().max_description_len(Some(4)).build().unwrap();
+        schedule.replace_block(1, limited).unwrap();
+        let json = schedule.to_json().unwrap();
+        assert_eq!(json.matches("max_description_len").count(), 1);
+        let mut loaded = Schedule::from_json(&json).unwrap();
+        assert_eq!(loaded.blocks()[1].max_description_len(), Some(4));
+        assert_eq!(loaded.blocks()[0].max_description_len(), None);
+        let mut block = loaded.remove_block(1).unwrap();
+        assert!(block.set_description(Some("一二三四五".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_load_versioned_file() {
+        let json = r#"{"version": 1, "day": {"blocks": [
+            {"start_time": "09:00", "end_time": "10:00", "name": "晨会"}
+        ]}}"#;
+        let schedule = Schedule::from_json(json).unwrap();
+        assert_eq!(schedule.blocks()[0].name(), "晨会");
+        assert!(sample().to_json().unwrap().contains("\"version\": 1"));
+    }
+
+    #[test]
+    fn test_migrate_unversioned_file() {
+        let json = r#"{"blocks": [{"start_time": "09:00", "end_time": "10:00", "name": "晨会"}]}"#;
+        assert_eq!(Schedule::from_json(json).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_reject_newer_version() {
+        let _locale = utils::lock_locale();
+        let json = r#"{"version": 99, "day": {"blocks": []}}"#;
+        let result = Schedule::from_json(json);
+        assert!(matches!(result, Err(ScheduleError::UnsupportedVersion { found: 99, supported: SCHEMA_VERSION })));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "文件格式版本 99 过新，当前程序最高支持版本 1，请升级程序"
+        );
+    }
+
+    #[test]
+    fn test_from_json_rejects_overlap() {
+        let json = r#"{"blocks": [
+            {"start_time": "09:00", "end_time": "10:00", "name": "甲"},
+            {"start_time": "09:30", "end_time": "11:00", "name": "乙"}
+        ]}"#;
+        assert!(matches!(Schedule::from_json(json), Err(ScheduleError::Overlap { .. })));
+    }
+
+    #[test]
+    fn test_from_json_rejects_bad_time() {
+        let json = r#"{"blocks": [{"start_time": "25:00", "end_time": "26:00", "name": "甲"}]}"#;
+        assert!(matches!(Schedule::from_json(json), Err(ScheduleError::Json(_))));
+    }
+
+    #[test]
+    fn test_save_and_load_file() {
+        let path = std::env::temp_dir().join(format!("timeblock-storage-{}.json", std::process::id()));
+        let schedule = sample();
+        schedule.save_to_file(&path).unwrap();
+        let loaded = Schedule::load_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.blocks(), schedule.blocks());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let result = Schedule::load_from_file("/nonexistent/timeblock.json");
+        assert!(matches!(result, Err(ScheduleError::Io(_))));
+    }
+}
@@ -0,0 +1,30 @@
+use crate::data::block::Block;
+
+/// 可复用的一日模板（例如固定的作息）
+#[derive(Debug, Clone, Default)]
+pub struct Template {
+    /// 模板名称
+    name: String,
+    /// 模板中的时间段
+    blocks: Vec<Block>,
+}
+
+impl Template {
+    /// 创建空模板
+    pub fn new(name: String) -> Template {
+        Template { name, blocks: Vec::new() }
+    }
+    /// 追加一个时间段
+    pub fn with_block(mut self, block: Block) -> Template {
+        self.blocks.push(block);
+        self
+    }
+    /// 获取模板名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// 获取模板中的时间段
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+}
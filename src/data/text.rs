@@ -0,0 +1,97 @@
+//! 简单的行式文本格式，便于快速录入日程
+//!
+//! 每行一个时间段：`09:00-10:30 会议 | 项目讨论`，`|` 之后为可选描述；
+//! 空行和以 `#` 开头的行会被忽略。
+
+use crate::data::block::Block;
+use crate::data::day::{Schedule, ScheduleError};
+use crate::data::timeofday::TimeOfDay;
+
+impl Schedule {
+    /// 从行式文本解析日程，错误信息中包含出错的行号
+    pub fn from_text(input: &str) -> Result<Schedule, ScheduleError> {
+        let mut schedule = Schedule::new();
+        for (index, raw) in input.lines().enumerate() {
+            let line = index + 1;
+            let content = raw.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            let block = parse_line(content).map_err(|reason| ScheduleError::Parse { line, reason })?;
+            schedule
+                .add_block(block)
+                .map_err(|e| ScheduleError::Parse { line, reason: e.to_string() })?;
+        }
+        schedule.clear_history();
+        Ok(schedule)
+    }
+}
+
+/// 解析单行内容为时间段
+fn parse_line(content: &str) -> Result<Block, String> {
+    let (range, rest) = content
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| format!("缺少名称：{}", content))?;
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| format!("时间范围格式不正确：{}", range))?;
+    let start: TimeOfDay = start.parse().map_err(|e| format!("{}", e))?;
+    let end: TimeOfDay = end.parse().map_err(|e| format!("{}", e))?;
+    let (name, description) = match rest.split_once('|') {
+        Some((name, description)) => (name.trim(), Some(description.trim())),
+        None => (rest.trim(), None),
+    };
+    let description = description.filter(|d| !d.is_empty()).map(str::to_string);
+    Block::builder()
+        .start_time(start)
+        .end_time(end)
+        .name(name.to_string())
+        .description(description)
+        .is_fixed(false)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_text_multiple_blocks() {
+        let input = "09:00-10:30 会议 | 项目讨论\n\n12:00-13:00 午饭\n";
+        let schedule = Schedule::from_text(input).unwrap();
+
+        assert_eq!(schedule.len(), 2);
+        let first = &schedule.blocks()[0];
+        assert_eq!(first.start_time(), TimeOfDay::new(9, 0).unwrap());
+        assert_eq!(first.end_time(), TimeOfDay::new(10, 30).unwrap());
+        assert_eq!(first.name(), "会议");
+        assert_eq!(first.description(), Some("项目讨论"));
+        let second = &schedule.blocks()[1];
+        assert_eq!(second.name(), "午饭");
+        assert_eq!(second.description(), None);
+    }
+
+    #[test]
+    fn test_from_text_comment_line() {
+        let input = "# 周一计划\n09:00-10:00 晨会\n  # 缩进的注释\n";
+        let schedule = Schedule::from_text(input).unwrap();
+
+        assert_eq!(schedule.len(), 1);
+        assert_eq!(schedule.blocks()[0].name(), "晨会");
+    }
+
+    #[test]
+    fn test_from_text_malformed_time() {
+        let input = "09:00-10:00 晨会\n9点-10:00 错误\n";
+        let result = Schedule::from_text(input);
+        assert!(matches!(result, Err(ScheduleError::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_from_text_overlap_reports_line() {
+        let input = "09:00-10:00 晨会\n# 注释\n09:30-10:30 冲突\n";
+        let result = Schedule::from_text(input);
+        assert!(matches!(result, Err(ScheduleError::Parse { line: 3, .. })));
+    }
+}
@@ -1,10 +1,22 @@
 use std::fmt;
+use std::str::FromStr;
 use log::error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::utils::{self, Locale};
+/// 一天的分钟数
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TimeOfDay(u16);
 
 
 impl TimeOfDay{
+    /// 午夜 00:00
+    pub const MIDNIGHT: TimeOfDay = TimeOfDay(0);
+    /// 正午 12:00
+    pub const NOON: TimeOfDay = TimeOfDay(12 * 60);
+    /// 一天中的最后一分钟 23:59，用作“直到一天结束”的结束时间
+    pub const END_OF_DAY: TimeOfDay = TimeOfDay(MINUTES_PER_DAY - 1);
     /// 创建时间
     pub fn new(hour: u16, minute: u16) -> Option<TimeOfDay> {
         if hour > 23 || minute > 59 {
@@ -14,13 +26,113 @@ impl TimeOfDay{
         Some(TimeOfDay(hour * 60 + minute))
     }
     /// 获取小时
-    pub fn hour(&self) -> u16 {
+    pub const fn hour(&self) -> u16 {
         self.0 / 60
     }
     /// 获取分钟
-    pub fn minute(&self) -> u16 {
+    pub const fn minute(&self) -> u16 {
         self.0 % 60
     }
+    /// 由午夜起的分钟数创建时间，超出一天范围返回 None
+    pub fn from_minutes_since_midnight(mins: u16) -> Option<TimeOfDay> {
+        TimeOfDay::try_from(mins).ok()
+    }
+    /// 时间在一天中所占的比例，范围 [0.0, 1.0)，用于按比例绘制时间轴
+    pub fn fraction_of_day(&self) -> f32 {
+        f32::from(self.0) / f32::from(MINUTES_PER_DAY)
+    }
+    /// 由一天中的比例得到时间（四舍五入到分钟），超出 [0.0, 1.0) 时返回 None
+    pub fn from_fraction(f: f32) -> Option<TimeOfDay> {
+        if !(0.0..1.0).contains(&f) {
+            return None;
+        }
+        let minutes = (f * f32::from(MINUTES_PER_DAY)).round().min(f32::from(MINUTES_PER_DAY - 1));
+        Some(TimeOfDay(minutes as u16))
+    }
+    /// 宽松地解析时间，用于导入格式不统一的数据
+    ///
+    /// 支持 "09:05"、"9.05"、"0905"（三或四位数字）以及带 AM/PM 后缀的 12 小时制（如 "9:05 AM"）。
+    /// 严格的 `FromStr` 只接受冒号分隔。
+    pub fn parse_flexible(s: &str) -> Result<TimeOfDay, TimeParseError> {
+        let invalid = || TimeParseError::InvalidFormat(s.to_string());
+        let text = s.trim();
+        let upper = text.to_ascii_uppercase();
+        let (body, meridiem) = match upper.strip_suffix("AM").or_else(|| upper.strip_suffix("PM")) {
+            Some(body) => (body.trim_end(), Some(upper.ends_with("PM"))),
+            None => (text, None),
+        };
+        let (hour, minute) = match body.split_once([':', '.']) {
+            Some(parts) => parts,
+            None if (3..=4).contains(&body.len()) && body.is_ascii() => body.split_at(body.len() - 2),
+            None => return Err(invalid()),
+        };
+        let all_digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+        if !all_digits(hour) || hour.len() > 2 || !all_digits(minute) || minute.len() != 2 {
+            return Err(invalid());
+        }
+        let mut hour: u16 = hour.parse().map_err(|_| invalid())?;
+        let minute: u16 = minute.parse().map_err(|_| invalid())?;
+        if let Some(pm) = meridiem {
+            if !(1..=12).contains(&hour) {
+                return Err(TimeParseError::OutOfRange(s.to_string()));
+            }
+            // 12 AM 为午夜，12 PM 为正午
+            hour = hour % 12 + if pm { 12 } else { 0 };
+        }
+        if hour > 23 || minute > 59 {
+            return Err(TimeParseError::OutOfRange(s.to_string()));
+        }
+        Ok(TimeOfDay(hour * 60 + minute))
+    }
+    /// 四舍五入到 `step_minutes` 的整数倍（用于网格吸附），超出一天时向下取整；步长为 0 时原样返回
+    pub fn round_to(self, step_minutes: u16) -> TimeOfDay {
+        if step_minutes == 0 {
+            return self;
+        }
+        let down = self.0 - self.0 % step_minutes;
+        let up = down.saturating_add(step_minutes);
+        if self.0 - down >= up - self.0 && up < MINUTES_PER_DAY {
+            TimeOfDay(up)
+        } else {
+            TimeOfDay(down)
+        }
+    }
+    /// 将时间限制在 [min, max] 范围内（例如把拖拽位置限制在工作时间内）
+    ///
+    /// # Panics
+    /// 当 `min > max` 时 panic。
+    pub fn clamp(self, min: TimeOfDay, max: TimeOfDay) -> TimeOfDay {
+        assert!(min <= max, "clamp 的下界 {} 不能晚于上界 {}", min, max);
+        Ord::clamp(self, min, max)
+    }
+    /// 由午夜起的分钟数创建时间，超出一天时取 23:59；任意输入都得到合法时间，便于模糊测试
+    pub fn saturating_from_minutes(mins: u16) -> TimeOfDay {
+        TimeOfDay(mins.min(MINUTES_PER_DAY - 1))
+    }
+    /// 由任意分钟数（可为负或超过一天）按 24 小时取模创建时间，例如 1500 为 01:00，-60 为 23:00
+    pub fn wrapping_from_minutes(mins: i32) -> TimeOfDay {
+        TimeOfDay(mins.rem_euclid(i32::from(MINUTES_PER_DAY)) as u16)
+    }
+    /// 12 小时制的小时（1 到 12），午夜和正午都为 12
+    pub const fn hour12(&self) -> u16 {
+        match self.hour() % 12 {
+            0 => 12,
+            h => h,
+        }
+    }
+    /// 是否为下午（12:00 及之后）
+    pub const fn is_pm(&self) -> bool {
+        self.hour() >= 12
+    }
+    /// 12 小时制表示，例如 "9:00am"、"12:30pm"（午夜为 "12:00am"）
+    pub fn to_12h(&self) -> String {
+        let suffix = if self.is_pm() { "pm" } else { "am" };
+        format!("{}:{:02}{}", self.hour12(), self.minute(), suffix)
+    }
+    /// 获取午夜起的分钟数
+    pub fn minutes_since_midnight(&self) -> u16 {
+        u16::from(*self)
+    }
 }
 
 impl From<TimeOfDay> for u16 {
@@ -33,7 +145,7 @@ impl TryFrom<u16> for TimeOfDay {
     type Error = ();
     ///u16转为TimeOfDay
     fn try_from(u: u16) -> Result<TimeOfDay, ()> {
-        if u>=24*60 {
+        if u>=MINUTES_PER_DAY {
             return Err(())
         }
         Ok(TimeOfDay(u))
@@ -47,6 +159,65 @@ impl fmt::Display for TimeOfDay {
     }
 }
 
+impl FromStr for TimeOfDay {
+    type Err = TimeParseError;
+    /// 解析 "HH:MM" 格式的时间（小时可为一位数）
+    fn from_str(s: &str) -> Result<TimeOfDay, TimeParseError> {
+        let (hour, minute) = s
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| TimeParseError::InvalidFormat(s.to_string()))?;
+        let all_digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+        if !all_digits(hour) || hour.len() > 2 || !all_digits(minute) || minute.len() != 2 {
+            return Err(TimeParseError::InvalidFormat(s.to_string()));
+        }
+        let hour: u16 = hour.parse().map_err(|_| TimeParseError::InvalidFormat(s.to_string()))?;
+        let minute: u16 = minute.parse().map_err(|_| TimeParseError::InvalidFormat(s.to_string()))?;
+        if hour > 23 || minute > 59 {
+            return Err(TimeParseError::OutOfRange(s.to_string()));
+        }
+        Ok(TimeOfDay(hour * 60 + minute))
+    }
+}
+
+impl Serialize for TimeOfDay {
+    /// 序列化为 "HH:MM" 字符串
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOfDay {
+    /// 从 "HH:MM" 字符串反序列化
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TimeOfDay, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// 表示解析时间字符串时可能发生的错误。
+#[derive(Debug, PartialEq, Eq)]
+pub enum TimeParseError {
+    /// 格式不正确
+    InvalidFormat(String),
+    /// 小时或分钟超出范围
+    OutOfRange(String),
+}
+
+impl fmt::Display for TimeParseError {
+    /// 按当前语言格式化错误信息
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, utils::locale()) {
+            (TimeParseError::InvalidFormat(s), Locale::ZhCn) => write!(f, "时间格式不正确：{}", s),
+            (TimeParseError::InvalidFormat(s), Locale::En) => write!(f, "Invalid time format: {}", s),
+            (TimeParseError::OutOfRange(s), Locale::ZhCn) => write!(f, "时间超出范围：{}", s),
+            (TimeParseError::OutOfRange(s), Locale::En) => write!(f, "Time out of range: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for TimeParseError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +294,228 @@ mod tests {
         // 相等性检查
         assert_eq!(TimeOfDay::new(12, 0), TimeOfDay::new(12, 0));
     }
-}
\ No newline at end of file
+
+    /// 具名分钟数转换测试
+    #[test]
+    fn minutes_since_midnight_roundtrip() {
+        // 90 -> 01:30
+        let t = TimeOfDay::from_minutes_since_midnight(90).expect("90 分钟应该合法");
+        assert_eq!(t.hour(), 1);
+        assert_eq!(t.minute(), 30);
+        assert_eq!(t.minutes_since_midnight(), 90);
+
+        // 非法边界
+        assert!(TimeOfDay::from_minutes_since_midnight(24 * 60).is_none(), "1440 不合法");
+        assert!(TimeOfDay::from_minutes_since_midnight(24 * 60 + 1).is_none(), "1441 不合法");
+
+        // 合法边界：0 -> 00:00，1439 -> 23:59
+        let first = TimeOfDay::from_minutes_since_midnight(0).expect("0 合法");
+        assert_eq!(first.minutes_since_midnight(), 0);
+        let last = TimeOfDay::from_minutes_since_midnight(24 * 60 - 1).expect("1439 合法");
+        assert_eq!(last.hour(), 23);
+        assert_eq!(last.minute(), 59);
+    }
+
+    /// 字符串解析测试
+    #[test]
+    fn from_str_parsing() {
+        assert_eq!("09:05".parse::<TimeOfDay>(), Ok(TimeOfDay::new(9, 5).unwrap()));
+        assert_eq!("9:05".parse::<TimeOfDay>(), Ok(TimeOfDay::new(9, 5).unwrap()));
+        assert_eq!("23:59".parse::<TimeOfDay>(), Ok(TimeOfDay::new(23, 59).unwrap()));
+
+        assert!(matches!("24:00".parse::<TimeOfDay>(), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!("09:60".parse::<TimeOfDay>(), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!("0905".parse::<TimeOfDay>(), Err(TimeParseError::InvalidFormat(_))));
+        assert!(matches!("9:5".parse::<TimeOfDay>(), Err(TimeParseError::InvalidFormat(_))));
+        assert!(matches!("ab:cd".parse::<TimeOfDay>(), Err(TimeParseError::InvalidFormat(_))));
+    }
+
+    /// 范围限制测试
+    #[test]
+    fn clamp_into_range() {
+        let min = TimeOfDay::new(8, 0).unwrap();
+        let max = TimeOfDay::new(18, 0).unwrap();
+
+        // 低于下界、高于上界
+        assert_eq!(TimeOfDay::new(7, 30).unwrap().clamp(min, max), min);
+        assert_eq!(TimeOfDay::new(20, 0).unwrap().clamp(min, max), max);
+        // 范围内
+        let inside = TimeOfDay::new(12, 15).unwrap();
+        assert_eq!(inside.clamp(min, max), inside);
+        // 恰好在边界上
+        assert_eq!(min.clamp(min, max), min);
+        assert_eq!(max.clamp(min, max), max);
+    }
+
+    /// 下界晚于上界时 panic
+    #[test]
+    #[should_panic]
+    fn clamp_invalid_range_panics() {
+        let t = TimeOfDay::new(12, 0).unwrap();
+        t.clamp(TimeOfDay::new(18, 0).unwrap(), TimeOfDay::new(8, 0).unwrap());
+    }
+
+    /// 一天比例换算测试
+    #[test]
+    fn fraction_of_day_and_back() {
+        assert_eq!(TimeOfDay::new(0, 0).unwrap().fraction_of_day(), 0.0);
+        assert_eq!(TimeOfDay::new(12, 0).unwrap().fraction_of_day(), 0.5);
+        let last = TimeOfDay::new(23, 59).unwrap().fraction_of_day();
+        assert!(last > 0.999 && last < 1.0);
+
+        assert_eq!(TimeOfDay::from_fraction(0.0), TimeOfDay::new(0, 0));
+        assert_eq!(TimeOfDay::from_fraction(0.5), TimeOfDay::new(12, 0));
+        assert!(TimeOfDay::from_fraction(1.0).is_none());
+        assert!(TimeOfDay::from_fraction(-0.1).is_none());
+        assert!(TimeOfDay::from_fraction(f32::NAN).is_none());
+
+        // 所有分钟往返稳定
+        for minutes in 0..24 * 60 {
+            let t = TimeOfDay::try_from(minutes).unwrap();
+            assert_eq!(TimeOfDay::from_fraction(t.fraction_of_day()), Some(t));
+        }
+    }
+
+    /// 网格吸附测试
+    #[test]
+    fn round_to_grid() {
+        let t = |h, m| TimeOfDay::new(h, m).unwrap();
+        assert_eq!(t(9, 7).round_to(15), t(9, 0));
+        assert_eq!(t(9, 8).round_to(15), t(9, 15));
+        assert_eq!(t(9, 30).round_to(15), t(9, 30));
+        // 恰好在中点时向上取整
+        assert_eq!(t(9, 5).round_to(10), t(9, 10));
+        // 向上取整会超出一天时向下取整
+        assert_eq!(t(23, 50).round_to(30), t(23, 30));
+        // 步长为 0 时原样返回
+        assert_eq!(t(9, 7).round_to(0), t(9, 7));
+    }
+
+    /// 宽松解析测试
+    #[test]
+    fn parse_flexible_formats() {
+        let t = |h, m| TimeOfDay::new(h, m).unwrap();
+        assert_eq!(TimeOfDay::parse_flexible("09:05"), Ok(t(9, 5)));
+        assert_eq!(TimeOfDay::parse_flexible("9.05"), Ok(t(9, 5)));
+        assert_eq!(TimeOfDay::parse_flexible("0905"), Ok(t(9, 5)));
+        assert_eq!(TimeOfDay::parse_flexible("905"), Ok(t(9, 5)));
+        assert_eq!(TimeOfDay::parse_flexible("9:05 AM"), Ok(t(9, 5)));
+        assert_eq!(TimeOfDay::parse_flexible("9:05pm"), Ok(t(21, 5)));
+        assert_eq!(TimeOfDay::parse_flexible("12:30 AM"), Ok(t(0, 30)));
+        assert_eq!(TimeOfDay::parse_flexible("12:30 PM"), Ok(t(12, 30)));
+        // 严格解析仍只接受冒号
+        assert!("9.05".parse::<TimeOfDay>().is_err());
+    }
+
+    /// 宽松解析拒绝无效输入
+    #[test]
+    fn parse_flexible_rejects() {
+        assert!(matches!(TimeOfDay::parse_flexible("abc"), Err(TimeParseError::InvalidFormat(_))));
+        assert!(matches!(TimeOfDay::parse_flexible("9"), Err(TimeParseError::InvalidFormat(_))));
+        assert!(matches!(TimeOfDay::parse_flexible("2460"), Err(TimeParseError::OutOfRange(_))));
+        assert!(matches!(TimeOfDay::parse_flexible("13:00 PM"), Err(TimeParseError::OutOfRange(_))));
+    }
+
+    /// 错误信息跟随当前语言
+    #[test]
+    fn parse_error_display_locale() {
+        let _locale = utils::lock_locale();
+        let error = "25:00".parse::<TimeOfDay>().unwrap_err();
+        assert_eq!(error.to_string(), "时间超出范围：25:00");
+        utils::set_locale(Locale::En);
+        assert_eq!(error.to_string(), "Time out of range: 25:00");
+        assert_eq!(TimeParseError::InvalidFormat("abc".to_string()).to_string(), "Invalid time format: abc");
+    }
+
+    /// 跨午夜取模测试
+    #[test]
+    fn wrapping_from_minutes() {
+        assert_eq!(TimeOfDay::wrapping_from_minutes(1500), TimeOfDay::new(1, 0).unwrap());
+        assert_eq!(TimeOfDay::wrapping_from_minutes(-60), TimeOfDay::new(23, 0).unwrap());
+        assert_eq!(TimeOfDay::wrapping_from_minutes(1440), TimeOfDay::new(0, 0).unwrap());
+        // 23:00 之后 3 小时
+        assert_eq!(TimeOfDay::wrapping_from_minutes(23 * 60 + 180), TimeOfDay::new(2, 0).unwrap());
+    }
+
+    /// 12 小时制测试
+    #[test]
+    fn to_12h() {
+        assert_eq!(TimeOfDay::new(9, 0).unwrap().to_12h(), "9:00am");
+        assert_eq!(TimeOfDay::new(0, 5).unwrap().to_12h(), "12:05am");
+        assert_eq!(TimeOfDay::new(12, 30).unwrap().to_12h(), "12:30pm");
+        assert_eq!(TimeOfDay::new(22, 45).unwrap().to_12h(), "10:45pm");
+    }
+
+    /// 12 小时制访问器测试
+    #[test]
+    fn hour12_and_is_pm() {
+        let at = |hour, minute| {
+            let t = TimeOfDay::new(hour, minute).unwrap();
+            (t.hour12(), t.is_pm())
+        };
+        assert_eq!(at(0, 0), (12, false));
+        assert_eq!(at(0, 30), (12, false));
+        assert_eq!(at(11, 59), (11, false));
+        assert_eq!(at(12, 0), (12, true));
+        assert_eq!(at(12, 30), (12, true));
+        assert_eq!(at(13, 0), (1, true));
+        assert_eq!(at(23, 59), (11, true));
+    }
+
+    /// 具名常量测试（可在 const 上下文中使用）
+    #[test]
+    fn named_constants() {
+        const LUNCH: (u16, u16) = (TimeOfDay::NOON.hour(), TimeOfDay::NOON.minute());
+        assert_eq!(LUNCH, (12, 0));
+        assert_eq!((TimeOfDay::MIDNIGHT.hour(), TimeOfDay::MIDNIGHT.minute()), (0, 0));
+        assert_eq!((TimeOfDay::END_OF_DAY.hour(), TimeOfDay::END_OF_DAY.minute()), (23, 59));
+        assert_eq!(TimeOfDay::NOON, TimeOfDay::new(12, 0).unwrap());
+        assert!(TimeOfDay::MIDNIGHT < TimeOfDay::NOON && TimeOfDay::NOON < TimeOfDay::END_OF_DAY);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        /// 合法分钟数经 TryFrom 与 From 往返不变，且小时、分钟能还原存储值
+        #[test]
+        fn valid_minutes_round_trip(mins in 0u16..MINUTES_PER_DAY) {
+            let t = TimeOfDay::try_from(mins).unwrap();
+            prop_assert_eq!(u16::from(t), mins);
+            prop_assert_eq!(t.hour() * 60 + t.minute(), mins);
+            prop_assert!(t.hour() < 24 && t.minute() < 60);
+        }
+
+        /// 超出一天的分钟数一律拒绝
+        #[test]
+        fn out_of_day_minutes_rejected(mins in MINUTES_PER_DAY..) {
+            prop_assert!(TimeOfDay::try_from(mins).is_err());
+        }
+
+        /// 任意输入经 saturating_from_minutes 都得到合法时间
+        #[test]
+        fn saturating_always_valid(mins in any::<u16>()) {
+            let t = TimeOfDay::saturating_from_minutes(mins);
+            prop_assert_eq!(TimeOfDay::try_from(u16::from(t)), Ok(t));
+            prop_assert_eq!(u16::from(t), mins.min(MINUTES_PER_DAY - 1));
+        }
+
+        /// 显示后再解析得到原时间
+        #[test]
+        fn display_parse_round_trip(mins in 0u16..MINUTES_PER_DAY) {
+            let t = TimeOfDay::try_from(mins).unwrap();
+            prop_assert_eq!(t.to_string().parse::<TimeOfDay>(), Ok(t));
+        }
+    }
+
+    /// 边界回归：一天的最后一分钟与第一分钟之外
+    #[test]
+    fn boundary_regression() {
+        assert_eq!(TimeOfDay::try_from(MINUTES_PER_DAY - 1), Ok(TimeOfDay::END_OF_DAY));
+        assert!(TimeOfDay::try_from(MINUTES_PER_DAY).is_err());
+        assert_eq!(TimeOfDay::saturating_from_minutes(u16::MAX), TimeOfDay::END_OF_DAY);
+    }
+}
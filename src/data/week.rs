@@ -0,0 +1,160 @@
+//! 一周的日程：每周重复的时间段加上只在某天出现的一次性时间段
+
+use std::fmt;
+
+use crate::data::block::Block;
+use crate::data::day::{Schedule, ScheduleError};
+use crate::utils::{self, Locale};
+
+/// 星期
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    /// 周一
+    Mon,
+    /// 周二
+    Tue,
+    /// 周三
+    Wed,
+    /// 周四
+    Thu,
+    /// 周五
+    Fri,
+    /// 周六
+    Sat,
+    /// 周日
+    Sun,
+}
+
+impl Weekday {
+    /// 从周一到周日的全部星期
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+}
+
+impl fmt::Display for Weekday {
+    /// 按当前语言显示为“周一”或“Mon”等
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match (self, utils::locale()) {
+            (Weekday::Mon, Locale::ZhCn) => "周一",
+            (Weekday::Tue, Locale::ZhCn) => "周二",
+            (Weekday::Wed, Locale::ZhCn) => "周三",
+            (Weekday::Thu, Locale::ZhCn) => "周四",
+            (Weekday::Fri, Locale::ZhCn) => "周五",
+            (Weekday::Sat, Locale::ZhCn) => "周六",
+            (Weekday::Sun, Locale::ZhCn) => "周日",
+            (Weekday::Mon, Locale::En) => "Mon",
+            (Weekday::Tue, Locale::En) => "Tue",
+            (Weekday::Wed, Locale::En) => "Wed",
+            (Weekday::Thu, Locale::En) => "Thu",
+            (Weekday::Fri, Locale::En) => "Fri",
+            (Weekday::Sat, Locale::En) => "Sat",
+            (Weekday::Sun, Locale::En) => "Sun",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 一周的日程
+///
+/// 添加时即校验：任意一天展开后的时间段都互不重叠。
+#[derive(Debug, Clone, Default)]
+pub struct WeeklySchedule {
+    /// 每周重复的时间段及其重复的星期
+    recurring: Vec<(Block, Vec<Weekday>)>,
+    /// 只在某一天出现的时间段
+    one_offs: Vec<(Weekday, Block)>,
+}
+
+impl WeeklySchedule {
+    /// 创建空的周日程
+    pub fn new() -> WeeklySchedule {
+        WeeklySchedule::default()
+    }
+    /// 添加在给定星期重复的时间段，与这些天已有的时间段重叠时返回错误
+    pub fn add_recurring(&mut self, block: Block, weekdays: &[Weekday]) -> Result<(), ScheduleError> {
+        for &weekday in weekdays {
+            self.effective_day(weekday).add_block(block.clone())?;
+        }
+        self.recurring.push((block, weekdays.to_vec()));
+        Ok(())
+    }
+    /// 添加只在某一天出现的时间段，与当天已有的时间段重叠时返回错误
+    pub fn add_one_off(&mut self, weekday: Weekday, block: Block) -> Result<(), ScheduleError> {
+        self.effective_day(weekday).add_block(block.clone())?;
+        self.one_offs.push((weekday, block));
+        Ok(())
+    }
+    /// 展开某一天实际发生的全部时间段（一次性的加上当天重复的）
+    pub fn effective_day(&self, weekday: Weekday) -> Schedule {
+        let recurring = self
+            .recurring
+            .iter()
+            .filter(|(_, days)| days.contains(&weekday))
+            .map(|(block, _)| block.clone());
+        let one_offs = self
+            .one_offs
+            .iter()
+            .filter(|(day, _)| *day == weekday)
+            .map(|(_, block)| block.clone());
+        Schedule::try_from_blocks(recurring.chain(one_offs).collect()).expect("添加时已校验互不重叠")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::timeofday::TimeOfDay;
+
+    fn block(start: (u16, u16), end: (u16, u16), name: &str) -> Block {
+        Block::builder()
+            .start_time(TimeOfDay::new(start.0, start.1).unwrap())
+            .end_time(TimeOfDay::new(end.0, end.1).unwrap())
+            .name(name.to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_effective_day_recurring() {
+        let mut week = WeeklySchedule::new();
+        week.add_recurring(block((9, 0), (10, 0), "健身"), &[Weekday::Mon, Weekday::Wed])
+            .unwrap();
+        week.add_one_off(Weekday::Mon, block((14, 0), (15, 0), "面试")).unwrap();
+
+        let monday = week.effective_day(Weekday::Mon);
+        let names: Vec<&str> = monday.iter().map(|b| b.name()).collect();
+        assert_eq!(names, vec!["健身", "面试"]);
+        assert_eq!(week.effective_day(Weekday::Wed).len(), 1);
+        assert!(week.effective_day(Weekday::Tue).is_empty());
+    }
+
+    #[test]
+    fn test_add_rejects_overlap() {
+        let mut week = WeeklySchedule::new();
+        week.add_recurring(block((9, 0), (10, 0), "健身"), &[Weekday::Mon, Weekday::Wed])
+            .unwrap();
+        let result = week.add_one_off(Weekday::Wed, block((9, 30), (10, 30), "例会"));
+        assert!(matches!(result, Err(ScheduleError::Overlap { .. })));
+        // 其他天不冲突
+        week.add_one_off(Weekday::Tue, block((9, 30), (10, 30), "例会")).unwrap();
+        assert_eq!(week.effective_day(Weekday::Wed).len(), 1);
+    }
+
+    #[test]
+    fn test_weekday_display() {
+        let _locale = utils::lock_locale();
+        assert_eq!(Weekday::Mon.to_string(), "周一");
+        assert_eq!(Weekday::ALL.len(), 7);
+        utils::set_locale(Locale::En);
+        assert_eq!(Weekday::Mon.to_string(), "Mon");
+        assert_eq!(Weekday::Sun.to_string(), "Sun");
+    }
+}
@@ -0,0 +1,208 @@
+//! 后台自动保存：编辑后经过一段静默时间再写盘，连续编辑只触发一次写入
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use log::error;
+
+use crate::data::day::{Schedule, ScheduleError};
+use crate::data::storage;
+
+/// 发给后台线程的消息
+enum Message {
+    /// 日程已修改，需要保存
+    Dirty,
+    /// 立即保存尚未写入的修改，并回复结果
+    Flush(Sender<Result<(), ScheduleError>>),
+}
+
+/// 持有日程并在后台线程中防抖保存到 JSON 文件
+///
+/// 通过 `lock` 修改日程后调用 `mark_dirty`；最后一次标记之后 `debounce` 时间内没有新的标记才会写盘。
+/// 被丢弃时会先写入尚未保存的修改。
+pub struct AutoSaver {
+    /// 被保存的日程
+    schedule: Arc<Mutex<Schedule>>,
+    /// 已完成的写盘次数
+    saves: Arc<AtomicUsize>,
+    /// 通知后台线程的通道，丢弃后线程保存剩余修改并退出
+    sender: Option<Sender<Message>>,
+    /// 后台线程
+    worker: Option<JoinHandle<()>>,
+}
+
+impl AutoSaver {
+    /// 创建自动保存器，从空日程开始
+    pub fn new(path: impl Into<PathBuf>, debounce: Duration) -> AutoSaver {
+        AutoSaver::with_schedule(Schedule::new(), path, debounce)
+    }
+
+    /// 用已有日程创建自动保存器
+    pub fn with_schedule(schedule: Schedule, path: impl Into<PathBuf>, debounce: Duration) -> AutoSaver {
+        let schedule = Arc::new(Mutex::new(schedule));
+        let saves = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel();
+        let path = path.into();
+        let worker = {
+            let schedule = Arc::clone(&schedule);
+            let saves = Arc::clone(&saves);
+            thread::spawn(move || {
+                // 只在序列化时持锁，写盘期间界面线程仍可修改日程
+                let save = || {
+                    let json = lock(&schedule).to_json();
+                    let result = json.and_then(|json| storage::write_file_atomic(&path, &json));
+                    if result.is_ok() {
+                        saves.fetch_add(1, Ordering::SeqCst);
+                    }
+                    result
+                };
+                let mut dirty = false;
+                loop {
+                    let message = if dirty {
+                        receiver.recv_timeout(debounce)
+                    } else {
+                        receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+                    };
+                    match message {
+                        Ok(Message::Dirty) => dirty = true,
+                        Ok(Message::Flush(reply)) => {
+                            let result = if dirty { save() } else { Ok(()) };
+                            dirty = false;
+                            let _ = reply.send(result);
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            if let Err(e) = save() {
+                                error!("自动保存失败：{}", e);
+                            }
+                            dirty = false;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => {
+                            if dirty && let Err(e) = save() {
+                                error!("退出前保存失败：{}", e);
+                            }
+                            break;
+                        }
+                    }
+                }
+            })
+        };
+        AutoSaver {
+            schedule,
+            saves,
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// 锁定日程以读取或修改，修改后需调用 `mark_dirty`
+    pub fn lock(&self) -> MutexGuard<'_, Schedule> {
+        lock(&self.schedule)
+    }
+
+    /// 标记日程已修改，在防抖时间后保存
+    pub fn mark_dirty(&self) {
+        self.send(Message::Dirty);
+    }
+
+    /// 立即写入尚未保存的修改并等待完成，没有修改时不写盘
+    pub fn flush(&self) -> Result<(), ScheduleError> {
+        let (reply, result) = mpsc::channel();
+        self.send(Message::Flush(reply));
+        result.recv().unwrap_or(Ok(()))
+    }
+
+    /// 已完成的写盘次数
+    pub fn saves(&self) -> usize {
+        self.saves.load(Ordering::SeqCst)
+    }
+
+    fn send(&self, message: Message) {
+        if let Some(sender) = &self.sender
+            && sender.send(message).is_err()
+        {
+            error!("自动保存线程已退出");
+        }
+    }
+}
+
+impl Drop for AutoSaver {
+    /// 关闭通道并等待后台线程写完剩余修改
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// 锁定日程；即使其他线程在持锁时 panic，日程本身仍然可用
+fn lock(schedule: &Mutex<Schedule>) -> MutexGuard<'_, Schedule> {
+    schedule.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::block::Block;
+    use crate::data::timeofday::TimeOfDay;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("timeblock-autosave-{}-{}.json", name, std::process::id()))
+    }
+
+    fn block(start: u16, name: &str) -> Block {
+        Block::builder()
+            .start_time(TimeOfDay::new(start, 0).unwrap())
+            .end_time(TimeOfDay::new(start + 1, 0).unwrap())
+            .name(name.to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_flush_writes_current_state() {
+        let path = temp_path("flush");
+        let saver = AutoSaver::new(&path, Duration::from_secs(60));
+        saver.lock().add_block(block(9, "会议")).unwrap();
+        saver.mark_dirty();
+        saver.flush().unwrap();
+        let loaded = Schedule::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.blocks(), saver.lock().blocks());
+        assert_eq!(saver.saves(), 1);
+        // 没有新的修改时 flush 不写盘
+        saver.flush().unwrap();
+        assert_eq!(saver.saves(), 1);
+    }
+
+    #[test]
+    fn test_rapid_marks_coalesce() {
+        let path = temp_path("coalesce");
+        let saver = AutoSaver::new(&path, Duration::from_secs(60));
+        for hour in 8..12 {
+            saver.lock().add_block(block(hour, "专注")).unwrap();
+            saver.mark_dirty();
+        }
+        saver.flush().unwrap();
+        assert_eq!(saver.saves(), 1);
+        assert_eq!(Schedule::load_from_file(&path).unwrap().len(), 4);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_drop_saves_pending_changes() {
+        let path = temp_path("drop");
+        {
+            let saver = AutoSaver::new(&path, Duration::from_secs(60));
+            saver.lock().add_block(block(14, "复盘")).unwrap();
+            saver.mark_dirty();
+        }
+        assert_eq!(Schedule::load_from_file(&path).unwrap().len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,2 +1,7 @@
 // src/gui/mod.rs
-//! GUI 模块（仅框架，暂无实现）
\ No newline at end of file
+//! GUI 模块：基于 egui 的界面组件（需启用 `gui` feature）
+mod autosave;
+mod timeline;
+
+pub use autosave::AutoSaver;
+pub use timeline::{TimelineEvent, TimelineWidget};
@@ -0,0 +1,284 @@
+use egui::{Align2, Color32, CornerRadius, FontId, Pos2, Rect, Sense, Stroke, StrokeKind, Ui, Vec2};
+use log::warn;
+
+use crate::data::block::Block;
+use crate::data::day::Schedule;
+use crate::data::timeofday::TimeOfDay;
+use crate::utils;
+
+/// 左侧时间轴的宽度（像素）
+const AXIS_WIDTH: f32 = 48.0;
+/// 固定时间段的颜色
+const FIXED_COLOR: Color32 = Color32::from_rgb(0x5b, 0x7d, 0xb1);
+/// 非固定时间段的颜色
+const FLEXIBLE_COLOR: Color32 = Color32::from_rgb(0x6a, 0xa8, 0x6f);
+
+/// 时间轴组件产生的事件
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimelineEvent {
+    /// 点击了某个时间段（日程中的索引）
+    BlockClicked(usize),
+    /// 拖拽移动了某个时间段（移动后在日程中的索引）
+    BlockMoved(usize),
+    /// 拖拽会造成重叠等问题，已被拒绝（日程中的索引）
+    MoveRejected(usize),
+}
+
+/// 时间轴显示的日程：只读或可编辑
+enum Source<'a> {
+    ReadOnly(&'a Schedule),
+    Editable(&'a mut Schedule),
+}
+
+/// 纵向 24 小时时间轴，按比例把日程中的时间段绘制为彩色矩形
+///
+/// 用 `new` 创建时只读；用 `editable` 创建时可以拖拽非固定时间段改变开始时间。
+pub struct TimelineWidget<'a> {
+    /// 要显示的日程
+    source: Source<'a>,
+    /// 每小时的高度（像素）
+    hour_height: f32,
+    /// 高亮显示的时间段
+    selected: Option<usize>,
+    /// 拖拽吸附的网格（分钟）
+    grid_minutes: u16,
+}
+
+impl<'a> TimelineWidget<'a> {
+    /// 创建只读的时间轴组件
+    pub fn new(schedule: &'a Schedule) -> TimelineWidget<'a> {
+        TimelineWidget::with_source(Source::ReadOnly(schedule))
+    }
+
+    /// 创建可拖拽编辑的时间轴组件，拖拽结束时通过 `Schedule::reschedule_block` 写回
+    pub fn editable(schedule: &'a mut Schedule) -> TimelineWidget<'a> {
+        TimelineWidget::with_source(Source::Editable(schedule))
+    }
+
+    fn with_source(source: Source<'a>) -> TimelineWidget<'a> {
+        TimelineWidget {
+            source,
+            hour_height: 48.0,
+            selected: None,
+            grid_minutes: 15,
+        }
+    }
+
+    /// 设置拖拽吸附的网格（分钟）
+    pub fn grid_minutes(mut self, grid_minutes: u16) -> TimelineWidget<'a> {
+        self.grid_minutes = grid_minutes;
+        self
+    }
+
+    /// 设置每小时的高度
+    pub fn hour_height(mut self, hour_height: f32) -> TimelineWidget<'a> {
+        self.hour_height = hour_height;
+        self
+    }
+
+    /// 设置高亮显示的时间段
+    pub fn selected(mut self, selected: Option<usize>) -> TimelineWidget<'a> {
+        self.selected = selected;
+        self
+    }
+
+    /// 绘制时间轴，返回本帧产生的事件
+    pub fn show(self, ui: &mut Ui) -> Option<TimelineEvent> {
+        let height = self.hour_height * 24.0;
+        let (rect, _) = ui.allocate_exact_size(Vec2::new(ui.available_width(), height), Sense::hover());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+
+        // 小时刻度与标签
+        for hour in 0..=24u16 {
+            let y = rect.top() + f32::from(hour) * self.hour_height;
+            painter.line_segment(
+                [Pos2::new(rect.left() + AXIS_WIDTH, y), Pos2::new(rect.right(), y)],
+                Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color),
+            );
+            if hour < 24 {
+                painter.text(
+                    Pos2::new(rect.left() + AXIS_WIDTH - 6.0, y),
+                    Align2::RIGHT_TOP,
+                    format!("{:02}:00", hour),
+                    FontId::monospace(11.0),
+                    visuals.weak_text_color(),
+                );
+            }
+        }
+
+        let schedule = match &self.source {
+            Source::ReadOnly(schedule) => *schedule,
+            Source::Editable(schedule) => &**schedule,
+        };
+        let editable = matches!(self.source, Source::Editable(_));
+        let mut event = None;
+        let mut pending_move = None;
+        for (index, block) in schedule.iter().enumerate() {
+            let id = ui.id().with(("timeline_block", index));
+            let draggable = editable && !block.is_fixed() && !block.is_locked();
+            // 拖拽过程中按吸附后的位置预览
+            let drag_dy = ui.data(|d| d.get_temp::<f32>(id)).unwrap_or(0.0);
+            let preview = drag_target_start(block, drag_dy, self.hour_height, self.grid_minutes)
+                .unwrap_or(block.start_time());
+            let offset = (preview.fraction_of_day() - block.start_time().fraction_of_day()) * height;
+            let (top, bottom) = block_y_range(block, rect.top(), height);
+            let block_rect = Rect::from_min_max(
+                Pos2::new(rect.left() + AXIS_WIDTH + 4.0, top + offset),
+                Pos2::new(rect.right() - 4.0, bottom + offset),
+            );
+            let sense = if draggable { Sense::click_and_drag() } else { Sense::click() };
+            let response = ui.interact(block_rect, id, sense);
+            if response.dragged() {
+                ui.data_mut(|d| *d.get_temp_mut_or(id, 0.0f32) += response.drag_delta().y);
+            }
+            if response.drag_stopped() {
+                ui.data_mut(|d| d.remove::<f32>(id));
+                if preview != block.start_time() {
+                    pending_move = Some((index, preview));
+                }
+            }
+            let color = block_color(block);
+            let fill = if response.hovered() { color.gamma_multiply(1.2) } else { color };
+            painter.rect_filled(block_rect, CornerRadius::same(4), fill);
+            if self.selected == Some(index) {
+                painter.rect_stroke(
+                    block_rect,
+                    CornerRadius::same(4),
+                    Stroke::new(2.0, visuals.selection.stroke.color),
+                    StrokeKind::Inside,
+                );
+            }
+            painter.with_clip_rect(block_rect).text(
+                block_rect.left_top() + Vec2::new(6.0, 4.0),
+                Align2::LEFT_TOP,
+                format!("{} {}-{}", block.name(), block.start_time(), block.end_time()),
+                FontId::proportional(13.0),
+                Color32::WHITE,
+            );
+            if response.clicked() {
+                event = Some(TimelineEvent::BlockClicked(index));
+            }
+        }
+
+        // 拖拽结束后写回日程；失败时日程不变，下一帧时间段自然回到原位
+        if let (Some((index, start)), Source::Editable(schedule)) = (pending_move, self.source) {
+            let block = &schedule.blocks()[index];
+            let end = start.checked_add(block.duration()).expect("拖拽目标已限制在一天之内");
+            event = Some(match schedule.reschedule_block(index, start, end) {
+                Ok(()) => TimelineEvent::BlockMoved(schedule.index_of_time(start) - 1),
+                Err(e) => {
+                    warn!("拖拽被拒绝：{}", e);
+                    TimelineEvent::MoveRejected(index)
+                }
+            });
+        }
+        event
+    }
+}
+
+/// 根据纵向拖拽距离计算新的开始时间（吸附到网格），保持时长且不超出一天
+///
+/// 拖拽距离为 0 或结果与原时间相同时也会返回原开始时间。
+fn drag_target_start(block: &Block, drag_dy: f32, hour_height: f32, grid_minutes: u16) -> Option<TimeOfDay> {
+    if hour_height <= 0.0 {
+        return None;
+    }
+    let duration = i32::from(block.duration().minutes());
+    let latest_start = 24 * 60 - 1 - duration;
+    let delta = (drag_dy / hour_height * 60.0).round() as i32;
+    let start = (i32::from(u16::from(block.start_time())) + delta).clamp(0, latest_start);
+    let snapped = TimeOfDay::try_from(start as u16).ok()?.round_to(grid_minutes);
+    // 吸附可能把开始时间推到 latest_start 之后，此时退回一格
+    if i32::from(u16::from(snapped)) > latest_start {
+        let step = grid_minutes.max(1);
+        return TimeOfDay::try_from(u16::from(snapped).saturating_sub(step)).ok();
+    }
+    Some(snapped)
+}
+
+/// 时间段的填充颜色：优先使用时间段自己的 `#rrggbb` 颜色，未设置或格式不对时按是否固定选择默认颜色
+fn block_color(block: &Block) -> Color32 {
+    let default = if block.is_fixed() { FIXED_COLOR } else { FLEXIBLE_COLOR };
+    let Some(hex) = block.color() else {
+        return default;
+    };
+    match Color32::from_hex(hex) {
+        Ok(color) => color,
+        Err(_) => {
+            utils::warn_once(&format!("timeline-color-{}", hex), &format!("无法识别的颜色 {}，使用默认颜色", hex));
+            default
+        }
+    }
+}
+
+/// 时间段在时间轴上的纵向像素范围 (上边缘, 下边缘)
+fn block_y_range(block: &Block, top: f32, height: f32) -> (f32, f32) {
+    (
+        top + block.start_time().fraction_of_day() * height,
+        top + block.end_time().fraction_of_day() * height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::timeofday::TimeOfDay;
+
+    #[test]
+    fn test_block_y_range() {
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(6, 0).unwrap())
+            .end_time(TimeOfDay::new(12, 0).unwrap())
+            .name("上午".to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap();
+
+        // 总高 240 像素：6:00 -> 60，12:00 -> 120，再加上顶部偏移
+        assert_eq!(block_y_range(&block, 10.0, 240.0), (70.0, 130.0));
+    }
+
+    #[test]
+    fn test_block_color() {
+        let block = |color: Option<&str>, is_fixed: bool| {
+            Block::builder()
+                .start_time(TimeOfDay::new(9, 0).unwrap())
+                .end_time(TimeOfDay::new(10, 0).unwrap())
+                .name("会议".to_string())
+                .is_fixed(is_fixed)
+                .color(color.map(str::to_string))
+                .build()
+                .unwrap()
+        };
+        assert_eq!(block_color(&block(Some("#ff8000"), true)), Color32::from_rgb(0xff, 0x80, 0x00));
+        // 未设置或格式不对时按是否固定选择默认颜色
+        assert_eq!(block_color(&block(None, true)), FIXED_COLOR);
+        assert_eq!(block_color(&block(None, false)), FLEXIBLE_COLOR);
+        assert_eq!(block_color(&block(Some("橙色"), false)), FLEXIBLE_COLOR);
+        assert_eq!(block_color(&block(Some("#12345"), true)), FIXED_COLOR);
+    }
+
+    #[test]
+    fn test_drag_target_start_snaps_to_grid() {
+        let block = Block::builder()
+            .start_time(TimeOfDay::new(9, 0).unwrap())
+            .end_time(TimeOfDay::new(10, 0).unwrap())
+            .name("会议".to_string())
+            .is_fixed(false)
+            .build()
+            .unwrap();
+        let t = |h, m| TimeOfDay::new(h, m).unwrap();
+
+        // 每小时 60 像素：1 像素 = 1 分钟
+        assert_eq!(drag_target_start(&block, 0.0, 60.0, 15), Some(t(9, 0)));
+        assert_eq!(drag_target_start(&block, 37.0, 60.0, 15), Some(t(9, 30)));
+        assert_eq!(drag_target_start(&block, 38.0, 60.0, 15), Some(t(9, 45)));
+        assert_eq!(drag_target_start(&block, -52.0, 60.0, 15), Some(t(8, 15)));
+        // 每小时 120 像素：30 像素 = 15 分钟
+        assert_eq!(drag_target_start(&block, 30.0, 120.0, 15), Some(t(9, 15)));
+        // 限制在一天之内，且保持时长
+        assert_eq!(drag_target_start(&block, -10_000.0, 60.0, 15), Some(t(0, 0)));
+        assert_eq!(drag_target_start(&block, 10_000.0, 60.0, 15), Some(t(22, 45)));
+    }
+}
@@ -0,0 +1,6 @@
+// src/lib.rs
+//! 时光块核心库：数据模型、工具函数，以及可选的 GUI 组件（`gui` feature）
+pub mod data;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod utils;
@@ -1,11 +1,9 @@
 // src/main.rs
 //! 应用入口（仅框架）
 //
-// 模块引入，类似 C++ 的 include
-mod data;
-mod gui;
-mod utils;
+// 数据与界面模块位于库中（src/lib.rs），这里只负责启动
+use timeblock::utils;
 
 fn main() {
     utils::init_logging();
-}
\ No newline at end of file
+}
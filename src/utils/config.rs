@@ -0,0 +1,229 @@
+//! 桌面应用的 TOML 配置
+//!
+//! 示例：
+//!
+//! ```toml
+//! day_start = "07:30"
+//! day_end = "23:00"
+//! grid_minutes = 30
+//! locale = "en"
+//! ```
+//!
+//! 缺少的键使用默认值：`day_start = "08:00"`、`day_end = "22:00"`、`grid_minutes = 15`、
+//! `locale = "zh-CN"`。
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::data::timeofday::{TimeOfDay, TimeParseError};
+use crate::utils::{self, Locale};
+
+/// 校验后的应用配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppConfig {
+    /// 一天的开始时间
+    pub day_start: TimeOfDay,
+    /// 一天的结束时间，晚于 `day_start`
+    pub day_end: TimeOfDay,
+    /// 时间轴网格的步长（分钟），大于 0
+    pub grid_minutes: u16,
+    /// 显示语言
+    pub locale: Locale,
+}
+
+impl Default for AppConfig {
+    fn default() -> AppConfig {
+        AppConfig {
+            day_start: TimeOfDay::new(8, 0).unwrap(),
+            day_end: TimeOfDay::new(22, 0).unwrap(),
+            grid_minutes: 15,
+            locale: Locale::ZhCn,
+        }
+    }
+}
+
+/// 配置文件的原始内容，时间与语言在校验时再解析
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    day_start: Option<String>,
+    day_end: Option<String>,
+    grid_minutes: Option<u16>,
+    locale: Option<String>,
+}
+
+/// 读取配置时可能发生的错误
+#[derive(Debug)]
+pub enum ConfigError {
+    /// 读取文件失败
+    Io(std::io::Error),
+    /// TOML 语法错误或字段类型不对
+    Toml(toml::de::Error),
+    /// 时间格式不正确
+    Time {
+        /// 出错的键
+        key: &'static str,
+        /// 解析错误
+        source: TimeParseError,
+    },
+    /// 一天的开始时间不早于结束时间
+    InvalidDayRange { start: TimeOfDay, end: TimeOfDay },
+    /// 网格步长为 0
+    InvalidGridStep,
+    /// 不支持的语言
+    UnknownLocale(String),
+}
+
+impl fmt::Display for ConfigError {
+    /// 按当前语言格式化错误信息
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, utils::locale()) {
+            (ConfigError::Io(err), Locale::ZhCn) => write!(f, "读取配置文件失败：{}", err),
+            (ConfigError::Io(err), Locale::En) => write!(f, "Failed to read config file: {}", err),
+            (ConfigError::Toml(err), Locale::ZhCn) => write!(f, "配置文件格式错误：{}", err),
+            (ConfigError::Toml(err), Locale::En) => write!(f, "Invalid config file: {}", err),
+            (ConfigError::Time { key, source }, Locale::ZhCn) => write!(f, "配置项 {} 无效：{}", key, source),
+            (ConfigError::Time { key, source }, Locale::En) => write!(f, "Invalid config key {}: {}", key, source),
+            (ConfigError::InvalidDayRange { start, end }, Locale::ZhCn) => {
+                write!(f, "一天的开始时间 {} 必须早于结束时间 {}", start, end)
+            }
+            (ConfigError::InvalidDayRange { start, end }, Locale::En) => {
+                write!(f, "Day start {} must be before day end {}", start, end)
+            }
+            (ConfigError::InvalidGridStep, Locale::ZhCn) => write!(f, "网格步长必须大于 0"),
+            (ConfigError::InvalidGridStep, Locale::En) => write!(f, "Grid step must be greater than 0"),
+            (ConfigError::UnknownLocale(locale), Locale::ZhCn) => write!(f, "不支持的语言：{}", locale),
+            (ConfigError::UnknownLocale(locale), Locale::En) => write!(f, "Unsupported locale: {}", locale),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            ConfigError::Toml(err) => Some(err),
+            ConfigError::Time { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> ConfigError {
+        ConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(err: toml::de::Error) -> ConfigError {
+        ConfigError::Toml(err)
+    }
+}
+
+/// 从 TOML 文件读取应用配置，缺少的键使用默认值
+pub fn load_config(path: &Path) -> Result<AppConfig, ConfigError> {
+    parse_config(&fs::read_to_string(path)?)
+}
+
+/// 解析并校验 TOML 配置文本
+fn parse_config(text: &str) -> Result<AppConfig, ConfigError> {
+    let raw: RawConfig = toml::from_str(text)?;
+    let defaults = AppConfig::default();
+    let parse_time = |key, value: Option<String>, default| match value {
+        Some(value) => value.parse().map_err(|source| ConfigError::Time { key, source }),
+        None => Ok(default),
+    };
+    let day_start = parse_time("day_start", raw.day_start, defaults.day_start)?;
+    let day_end = parse_time("day_end", raw.day_end, defaults.day_end)?;
+    if day_start >= day_end {
+        return Err(ConfigError::InvalidDayRange {
+            start: day_start,
+            end: day_end,
+        });
+    }
+    let grid_minutes = raw.grid_minutes.unwrap_or(defaults.grid_minutes);
+    if grid_minutes == 0 {
+        return Err(ConfigError::InvalidGridStep);
+    }
+    let locale = match raw.locale.as_deref() {
+        None => defaults.locale,
+        Some("zh-CN") => Locale::ZhCn,
+        Some("en") => Locale::En,
+        Some(other) => return Err(ConfigError::UnknownLocale(other.to_string())),
+    };
+    Ok(AppConfig {
+        day_start,
+        day_end,
+        grid_minutes,
+        locale,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_config() {
+        let config = parse_config(
+            "day_start = \"07:30\"\nday_end = \"23:00\"\ngrid_minutes = 30\nlocale = \"en\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                day_start: TimeOfDay::new(7, 30).unwrap(),
+                day_end: TimeOfDay::new(23, 0).unwrap(),
+                grid_minutes: 30,
+                locale: Locale::En,
+            }
+        );
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config = parse_config("day_end = \"20:00\"\n").unwrap();
+        assert_eq!(config.day_start, TimeOfDay::new(8, 0).unwrap());
+        assert_eq!(config.day_end, TimeOfDay::new(20, 0).unwrap());
+        assert_eq!(config.grid_minutes, 15);
+        assert_eq!(config.locale, Locale::ZhCn);
+        assert_eq!(parse_config("").unwrap(), AppConfig::default());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let result = parse_config("day_start = \"18:00\"\nday_end = \"09:00\"\n");
+        assert!(matches!(result, Err(ConfigError::InvalidDayRange { .. })));
+        let result = parse_config("day_start = \"25:00\"\n");
+        assert!(matches!(result, Err(ConfigError::Time { key: "day_start", .. })));
+        assert!(matches!(parse_config("grid_minutes = 0\n"), Err(ConfigError::InvalidGridStep)));
+        assert!(matches!(parse_config("locale = \"fr\"\n"), Err(ConfigError::UnknownLocale(_))));
+        assert!(matches!(parse_config("day_stat = \"08:00\"\n"), Err(ConfigError::Toml(_))));
+    }
+
+    #[test]
+    fn test_error_display_locale() {
+        let _locale = utils::lock_locale();
+        let error = parse_config("day_start = \"25:00\"\n").unwrap_err();
+        assert_eq!(error.to_string(), "配置项 day_start 无效：时间超出范围：25:00");
+        utils::set_locale(Locale::En);
+        assert_eq!(error.to_string(), "Invalid config key day_start: Time out of range: 25:00");
+    }
+
+    #[test]
+    fn test_load_config_file() {
+        let path = std::env::temp_dir().join(format!("timeblock-config-{}.toml", std::process::id()));
+        fs::write(&path, "grid_minutes = 10\n").unwrap();
+        let config = load_config(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(config.grid_minutes, 10);
+        assert!(matches!(
+            load_config(Path::new("/nonexistent/timeblock.toml")),
+            Err(ConfigError::Io(_))
+        ));
+    }
+}
@@ -1,4 +1,14 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
 use env_logger;
+use log::warn;
+
+mod config;
+
+pub use config::{AppConfig, ConfigError, load_config};
+
 pub fn init_logging() {
     use std::env;
     if env::var("RUST_LOG").is_err() {
@@ -8,4 +18,168 @@ pub fn init_logging() {
     let _ = env_logger::builder()
         .format_timestamp_millis()
         .try_init();
-}
\ No newline at end of file
+}
+
+/// 已经输出过 `warn_once` 警告的键
+static WARNED_ONCE: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+/// 每个键上一次输出 `warn_throttled` 警告的时间
+static LAST_WARNED: LazyLock<Mutex<HashMap<String, Instant>>> = LazyLock::new(Default::default);
+
+/// 每个键只输出一次警告，避免界面重绘循环中刷屏
+pub fn warn_once(key: &str, msg: &str) {
+    let first = WARNED_ONCE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(key.to_string());
+    if first {
+        warn!("{}", msg);
+    }
+}
+
+/// 每个键在 `interval` 内最多输出一次警告
+pub fn warn_throttled(key: &str, msg: &str, interval: Duration) {
+    let now = Instant::now();
+    let mut last_warned = LAST_WARNED.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(last) = last_warned.get(key)
+        && now.duration_since(*last) < interval
+    {
+        return;
+    }
+    last_warned.insert(key.to_string(), now);
+    drop(last_warned);
+    warn!("{}", msg);
+}
+
+/// 稳定的 FNV-1a 哈希，不随 Rust 版本和运行环境变化，用于需要持久一致的映射（如标签颜色、导出 UID）
+pub(crate) fn stable_hash(text: &str) -> u64 {
+    text.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// 用户可见文本（错误信息、时间描述）使用的语言
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// 简体中文（默认）
+    #[default]
+    ZhCn,
+    /// 英文
+    En,
+}
+
+/// 全局显示语言
+static LOCALE: RwLock<Locale> = RwLock::new(Locale::ZhCn);
+
+/// 设置显示语言，对所有线程（包括自动保存等后台线程）生效
+pub fn set_locale(locale: Locale) {
+    *LOCALE.write().unwrap_or_else(PoisonError::into_inner) = locale;
+}
+
+/// 获取当前的显示语言
+pub fn locale() -> Locale {
+    *LOCALE.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// 依赖显示语言的测试共用的锁，防止并行测试互相切换语言
+#[cfg(test)]
+static LOCALE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// 持有语言测试锁期间可以随意切换语言，释放时（包括测试失败时）恢复原来的语言
+#[cfg(test)]
+pub(crate) struct LocaleGuard {
+    previous: Locale,
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+impl Drop for LocaleGuard {
+    fn drop(&mut self) {
+        set_locale(self.previous);
+    }
+}
+
+/// 获取语言测试锁；输出随语言变化的测试都应先调用它
+#[cfg(test)]
+pub(crate) fn lock_locale() -> LocaleGuard {
+    let lock = LOCALE_TEST_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    LocaleGuard {
+        previous: locale(),
+        _lock: lock,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// 按线程记录日志内容的测试日志器，避免并行测试互相干扰
+    struct CountingLogger;
+
+    thread_local! {
+        static LOGGED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    impl log::Log for CountingLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &log::Record) {
+            LOGGED.with(|logged| logged.borrow_mut().push(record.args().to_string()));
+        }
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CountingLogger = CountingLogger;
+
+    /// 安装测试日志器，返回统计某条消息出现次数的函数
+    fn install_logger() -> impl Fn(&str) -> usize {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Warn);
+        |msg| LOGGED.with(|logged| logged.borrow().iter().filter(|m| *m == msg).count())
+    }
+
+    #[test]
+    fn test_warn_once() {
+        let count = install_logger();
+        for _ in 0..5 {
+            warn_once("test_warn_once", "字体加载失败");
+        }
+        assert_eq!(count("字体加载失败"), 1);
+        // 不同的键各自输出一次
+        warn_once("test_warn_once_other", "字体加载失败");
+        assert_eq!(count("字体加载失败"), 2);
+    }
+
+    #[test]
+    fn test_warn_throttled() {
+        let count = install_logger();
+        for _ in 0..5 {
+            warn_throttled("test_warn_throttled", "保存过于频繁", Duration::from_secs(60));
+        }
+        assert_eq!(count("保存过于频繁"), 1);
+        for _ in 0..3 {
+            warn_throttled("test_warn_throttled_zero", "重绘警告", Duration::ZERO);
+        }
+        assert_eq!(count("重绘警告"), 3);
+    }
+
+    #[test]
+    fn test_locale_default_and_switch() {
+        let _locale = lock_locale();
+        assert_eq!(locale(), Locale::ZhCn);
+        set_locale(Locale::En);
+        assert_eq!(locale(), Locale::En);
+        // 对其他线程同样生效
+        assert_eq!(std::thread::spawn(locale).join().unwrap(), Locale::En);
+    }
+
+    #[test]
+    fn test_locale_guard_restores() {
+        {
+            let _locale = lock_locale();
+            set_locale(Locale::En);
+        }
+        let _locale = lock_locale();
+        assert_eq!(locale(), Locale::ZhCn);
+    }
+}